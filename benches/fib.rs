@@ -0,0 +1,52 @@
+//! Benchmark of a recursive `fib`, which mostly measures the cost of looking
+//! up and calling user-defined functions.
+//!
+//! Run with `cargo bench --bench fib`. To see the effect of a change, run
+//! it on the trees before and after the change, on the same machine.
+
+use mankailib::{Interpreter, Lexer, MankaiObject, Parser};
+use std::time::{Duration, Instant};
+
+/// Evaluate a Mankai expression.
+fn run(source: &str, interpreter: &mut Interpreter) -> MankaiObject {
+    let mut lexer = Lexer::new(String::from(source));
+    lexer.scan().unwrap_or_else(|err| panic!("{}", err.message));
+
+    let mut parser = Parser::new(lexer.tokens);
+    let sexp = parser
+        .parse()
+        .unwrap_or_else(|err| panic!("{}", err.message));
+
+    interpreter
+        .evaluate(&sexp)
+        .unwrap_or_else(|err| panic!("{}", err.message))
+}
+
+/// Run `f` a few times and return its fastest run.
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let mut interpreter = Interpreter::new();
+    run(
+        "(defun! fib (n) (if! (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))",
+        &mut interpreter,
+    );
+
+    for n in &[15, 20] {
+        let source = format!("(fib {})", n);
+        let elapsed = time(|| {
+            run(&source, &mut interpreter);
+        });
+
+        println!("{:<12} {:>12.3?}", source, elapsed);
+    }
+}
@@ -0,0 +1,99 @@
+//! Benchmarks of `car`, `cdr` and `cons` on 10000-element lists.
+//!
+//! Run with `cargo bench --bench lists`. Each operation is timed both on
+//! `List` and on a plain `Vec` copied at every step, which is how lists were
+//! represented before.
+
+use mankailib::{List, MankaiObject, Number};
+use std::time::{Duration, Instant};
+
+/// Number of elements of the lists.
+const SIZE: usize = 10_000;
+
+fn element(i: usize) -> MankaiObject {
+    MankaiObject::Number(Number::from(i as i64))
+}
+
+/// Run `f` a few times and return its fastest run.
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, copying: Duration, persistent: Duration) {
+    println!(
+        "{:<28} {:>12.3?} {:>12.3?} {:>9.1}x",
+        name,
+        copying,
+        persistent,
+        copying.as_secs_f64() / persistent.as_secs_f64()
+    );
+}
+
+/// Build a list by repeatedly consing on the previous version.
+fn cons() {
+    let copying = time(|| {
+        let mut list = Vec::new();
+        for i in 0..SIZE {
+            let mut next = list.clone();
+            next.push(element(i));
+            list = next;
+        }
+        assert_eq!(list.len(), SIZE);
+    });
+    let persistent = time(|| {
+        let mut list = List::new();
+        for i in 0..SIZE {
+            let mut next = list.clone();
+            next.push(element(i));
+            list = next;
+        }
+        assert_eq!(list.len(), SIZE);
+    });
+    report("cons 10k elements", copying, persistent);
+}
+
+/// Walk a list with car and cdr.
+fn walk() {
+    let vector: Vec<MankaiObject> = (0..SIZE).map(element).collect();
+    let list: List = (0..SIZE).map(element).collect();
+    let copying = time(|| {
+        let mut rest = vector.clone();
+        let mut count = 0;
+        while let Some(first) = rest.first() {
+            if *first == element(count) {
+                count += 1;
+            }
+            rest = rest.iter().skip(1).cloned().collect();
+        }
+        assert_eq!(count, SIZE);
+    });
+    let persistent = time(|| {
+        let mut rest = list.clone();
+        let mut count = 0;
+        while let Some(first) = rest.first() {
+            if *first == element(count) {
+                count += 1;
+            }
+            rest = rest.rest();
+        }
+        assert_eq!(count, SIZE);
+    });
+    report("car/cdr walk of 10k", copying, persistent);
+}
+
+fn main() {
+    println!(
+        "{:<28} {:>12} {:>12} {:>10}",
+        "operation", "copying", "persistent", "speedup"
+    );
+
+    cons();
+    walk();
+}
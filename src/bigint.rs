@@ -0,0 +1,389 @@
+use std::cmp::Ordering;
+
+/// An arbitrary precision integer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    /// Sign of the number (zero is never negative).
+    negative: bool,
+    /// Magnitude of the number in base 2^32, least significant digit first and
+    /// without leading zeros (zero is the empty vector).
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    /// Make a new big integer from sign and magnitude, normalizing it.
+    fn new(negative: bool, digits: Vec<u32>) -> Self {
+        let digits = trim(digits);
+
+        BigInt {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    /// Zero.
+    pub fn zero() -> Self {
+        BigInt::new(false, Vec::new())
+    }
+
+    /// Make a big integer from a machine integer.
+    pub fn from_i64(n: i64) -> Self {
+        let magnitude = n.unsigned_abs();
+        BigInt::new(n < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+
+    /// Convert to a machine integer, if it fits.
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+
+        let mut magnitude = 0u64;
+        for (i, digit) in self.digits.iter().enumerate() {
+            magnitude |= u64::from(*digit) << (32 * i);
+        }
+
+        if self.negative {
+            if magnitude <= i64::MAX as u64 + 1 {
+                Some((magnitude as i64).wrapping_neg())
+            } else {
+                None
+            }
+        } else if magnitude <= i64::MAX as u64 {
+            Some(magnitude as i64)
+        } else {
+            None
+        }
+    }
+
+    /// Convert to the closest float (infinite if too big).
+    pub fn to_f64(&self) -> f64 {
+        let mut result = 0.0;
+        for digit in self.digits.iter().rev() {
+            result = result * 4_294_967_296.0 + f64::from(*digit);
+        }
+
+        if self.negative {
+            -result
+        } else {
+            result
+        }
+    }
+
+    /// Parse a string of digits in the given radix, with an optional leading
+    /// sign.
+    pub fn parse(string: &str, radix: u32) -> Option<Self> {
+        let (negative, digits) = match string.chars().next() {
+            Some('-') => (true, &string[1..]),
+            Some('+') => (false, &string[1..]),
+            _ => (false, string),
+        };
+
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut magnitude = Vec::new();
+        for c in digits.chars() {
+            let digit = c.to_digit(radix)?;
+            magnitude = mul_small(&magnitude, radix, digit);
+        }
+
+        Some(BigInt::new(negative, magnitude))
+    }
+
+    /// Check if the number is zero.
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Check if the number is negative.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Number of bits of the magnitude.
+    pub fn bits(&self) -> usize {
+        match self.digits.last() {
+            Some(top) => 32 * self.digits.len() - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Absolute value.
+    pub fn abs(&self) -> Self {
+        BigInt::new(false, self.digits.clone())
+    }
+
+    /// Quotient and remainder of the division truncated towards zero (the
+    /// remainder has the sign of the dividend). Panics if `other` is zero.
+    pub fn div_rem(&self, other: &BigInt) -> (Self, Self) {
+        if other.is_zero() {
+            panic!("division of a big integer by zero");
+        }
+
+        let (quotient, remainder) = div_rem_magnitudes(&self.digits, &other.digits);
+        (
+            BigInt::new(self.negative != other.negative, quotient),
+            BigInt::new(self.negative, remainder),
+        )
+    }
+
+    /// Greatest common divisor (always non-negative).
+    pub fn gcd(&self, other: &BigInt) -> Self {
+        let mut a = self.abs();
+        let mut b = other.abs();
+
+        while !b.is_zero() {
+            let (_, remainder) = a.div_rem(&b);
+            a = b;
+            b = remainder;
+        }
+
+        a
+    }
+}
+
+impl std::ops::Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+}
+
+impl std::ops::Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            BigInt::new(self.negative, add_magnitudes(&self.digits, &other.digits))
+        } else {
+            match compare_magnitudes(&self.digits, &other.digits) {
+                Ordering::Less => {
+                    BigInt::new(other.negative, sub_magnitudes(&other.digits, &self.digits))
+                }
+                _ => BigInt::new(self.negative, sub_magnitudes(&self.digits, &other.digits)),
+            }
+        }
+    }
+}
+
+impl std::ops::Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &(-other)
+    }
+}
+
+impl std::ops::Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+
+        for (i, a) in self.digits.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.digits.iter().enumerate() {
+                let current = u64::from(digits[i + j]) + u64::from(*a) * u64::from(*b) + carry;
+                digits[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+
+        BigInt::new(self.negative != other.negative, digits)
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.digits, &other.digits),
+            (true, true) => compare_magnitudes(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl std::fmt::Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Extract groups of nine decimal digits, least significant first.
+        let mut groups = Vec::new();
+        let mut magnitude = self.digits.clone();
+        while !magnitude.is_empty() {
+            let (quotient, remainder) = div_rem_small(&magnitude, 1_000_000_000);
+            groups.push(remainder);
+            magnitude = quotient;
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        write!(f, "{}", groups.last().unwrap())?;
+        for group in groups.iter().rev().skip(1) {
+            write!(f, "{:09}", group)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Operations on magnitudes (base 2^32 digits, least significant first).
+
+/// Remove leading zeros from a magnitude.
+fn trim(mut digits: Vec<u32>) -> Vec<u32> {
+    while let Some(&0) = digits.last() {
+        digits.pop();
+    }
+
+    digits
+}
+
+/// Compare two magnitudes.
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    if a.len() != b.len() {
+        return a.len().cmp(&b.len());
+    }
+
+    for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+        if x != y {
+            return x.cmp(y);
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Sum of two magnitudes.
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+
+    for i in 0..a.len().max(b.len()) {
+        let current =
+            u64::from(*a.get(i).unwrap_or(&0)) + u64::from(*b.get(i).unwrap_or(&0)) + carry;
+        result.push(current as u32);
+        carry = current >> 32;
+    }
+    result.push(carry as u32);
+
+    trim(result)
+}
+
+/// Difference of two magnitudes, `a` must not be smaller than `b`.
+fn sub_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+
+    for (i, digit) in a.iter().enumerate() {
+        let mut current = i64::from(*digit) - i64::from(*b.get(i).unwrap_or(&0)) - borrow;
+        if current < 0 {
+            current += 1 << 32;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+        result.push(current as u32);
+    }
+
+    trim(result)
+}
+
+/// Compute `a * factor + addend`.
+fn mul_small(a: &[u32], factor: u32, addend: u32) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = u64::from(addend);
+
+    for digit in a {
+        let current = u64::from(*digit) * u64::from(factor) + carry;
+        result.push(current as u32);
+        carry = current >> 32;
+    }
+    result.push(carry as u32);
+
+    trim(result)
+}
+
+/// Quotient and remainder of the division of a magnitude by a single digit.
+fn div_rem_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder = 0u64;
+
+    for (i, digit) in a.iter().enumerate().rev() {
+        let current = (remainder << 32) | u64::from(*digit);
+        quotient[i] = (current / u64::from(divisor)) as u32;
+        remainder = current % u64::from(divisor);
+    }
+
+    (trim(quotient), remainder as u32)
+}
+
+/// Quotient and remainder of the division of two magnitudes (the divisor
+/// must not be zero). Uses binary long division.
+fn div_rem_magnitudes(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if compare_magnitudes(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+
+    if b.len() == 1 {
+        let (quotient, remainder) = div_rem_small(a, b[0]);
+        return (quotient, trim(vec![remainder]));
+    }
+
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+
+    for i in (0..a.len() * 32).rev() {
+        // Shift the remainder left by one bit and bring down the next bit.
+        let bit = (a[i / 32] >> (i % 32)) & 1;
+        remainder = mul_small(&remainder, 2, bit);
+
+        if compare_magnitudes(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitudes(&remainder, b);
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+
+    (trim(quotient), remainder)
+}
+
+#[cfg(test)]
+mod bigint_test {
+    use super::BigInt;
+
+    #[test]
+    fn arithmetic() {
+        let a = BigInt::parse("123456789012345678901234567890", 10).unwrap();
+        let b = BigInt::parse("-987654321", 10).unwrap();
+
+        assert_eq!(
+            (&a * &b).to_string(),
+            "-121932631124828532112482853211126352690"
+        );
+        assert_eq!((&a + &b).to_string(), "123456789012345678900246913569");
+        assert_eq!((&b - &a).to_string(), "-123456789012345678902222222211");
+
+        let (quotient, remainder) = a.div_rem(&b);
+        assert_eq!(quotient.to_string(), "-124999998873437499901");
+        assert_eq!(remainder.to_string(), "574845669");
+        assert_eq!(&(&quotient * &b) + &remainder, a);
+
+        assert_eq!(BigInt::from_i64(i64::MIN).to_i64(), Some(i64::MIN));
+        assert_eq!(BigInt::parse("ff", 16).unwrap(), BigInt::from_i64(255));
+        assert_eq!(BigInt::parse("12a", 10), None);
+    }
+}
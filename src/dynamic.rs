@@ -0,0 +1,68 @@
+use crate::interpreter::{Interpreter, MankaiObject, RuntimeError};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct ParameterCell {
+    /// Current value of the parameter.
+    value: RefCell<MankaiObject>,
+    /// Function applied to the values given to the parameter, if any.
+    converter: Option<MankaiObject>,
+}
+
+/// A parameter made by `make-parameter`: a dynamic variable whose value can
+/// be changed by `parameterize!` for the time its body runs.
+#[derive(Clone)]
+pub struct Parameter(Rc<ParameterCell>);
+
+impl Parameter {
+    /// Make a parameter with a starting value. If there is a converter the
+    /// starting value goes through it.
+    pub fn new(
+        interpreter: &mut Interpreter,
+        value: MankaiObject,
+        converter: Option<MankaiObject>,
+    ) -> Result<Self, RuntimeError> {
+        let value = match &converter {
+            Some(converter) => converter.call(interpreter, vec![value])?,
+            None => value,
+        };
+
+        Ok(Parameter(Rc::new(ParameterCell {
+            value: RefCell::new(value),
+            converter,
+        })))
+    }
+
+    /// Get the current value of the parameter.
+    pub fn get(&self) -> MankaiObject {
+        self.0.value.borrow().clone()
+    }
+
+    /// Apply the converter of the parameter to a value it's going to be bound
+    /// to.
+    pub fn convert(
+        &self,
+        interpreter: &mut Interpreter,
+        value: MankaiObject,
+    ) -> Result<MankaiObject, RuntimeError> {
+        match &self.0.converter {
+            Some(converter) => converter.call(interpreter, vec![value]),
+            None => Ok(value),
+        }
+    }
+
+    /// Change the value of the parameter, returning the previous one.
+    pub fn replace(&self, value: MankaiObject) -> MankaiObject {
+        self.0.value.replace(value)
+    }
+
+    /// Check if two parameters are the same object.
+    pub fn ptr_eq(&self, other: &Parameter) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Address of the parameter, identifying it.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+}
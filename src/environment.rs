@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use crate::interpreter::{MankaiObject, RuntimeError};
+use crate::interpreter::{MankaiObject, RuntimeError, SpecialForm};
 use crate::native_functions;
+use crate::number::Number;
 use crate::special_forms;
 use crate::token::*;
 
@@ -19,32 +20,88 @@ impl Environment {
         let mut environment = Environment::default();
         environment.layers.push(HashMap::new());
 
-        let define = MankaiObject::SpecialForm(special_forms::define);
+        // Bring to scope some special forms.
+        let case_lambda =
+            MankaiObject::SpecialForm(SpecialForm::Function(special_forms::case_lambda));
+        environment.define(
+            &Token::new(String::from("case-lambda!"), TokenKind::Identifier),
+            case_lambda,
+        );
+
+        let define = MankaiObject::SpecialForm(SpecialForm::Define);
         environment.define(
             &Token::new(String::from("define!"), TokenKind::Identifier),
             define,
         );
 
-        // Bring to scope some special forms.
-        let defun = MankaiObject::SpecialForm(special_forms::defun);
+        let defrecord = MankaiObject::SpecialForm(SpecialForm::Function(special_forms::defrecord));
+        environment.define(
+            &Token::new(String::from("defrecord!"), TokenKind::Identifier),
+            defrecord,
+        );
+
+        let deftype = MankaiObject::SpecialForm(SpecialForm::Function(special_forms::deftype));
+        environment.define(
+            &Token::new(String::from("deftype!"), TokenKind::Identifier),
+            deftype,
+        );
+
+        let defun = MankaiObject::SpecialForm(SpecialForm::Function(special_forms::defun));
         environment.define(
             &Token::new(String::from("defun!"), TokenKind::Identifier),
             defun,
         );
 
-        let if_special_form = MankaiObject::SpecialForm(special_forms::if_special_form);
+        let delay = MankaiObject::SpecialForm(SpecialForm::Function(special_forms::delay));
+        environment.define(
+            &Token::new(String::from("delay!"), TokenKind::Identifier),
+            delay,
+        );
+
+        let if_special_form = MankaiObject::SpecialForm(SpecialForm::If);
         environment.define(
             &Token::new(String::from("if!"), TokenKind::Identifier),
             if_special_form,
         );
 
-        let lambda = MankaiObject::SpecialForm(special_forms::lambda);
+        let lambda = MankaiObject::SpecialForm(SpecialForm::Function(special_forms::lambda));
         environment.define(
             &Token::new(String::from("lambda!"), TokenKind::Identifier),
             lambda,
         );
 
-        let set = MankaiObject::SpecialForm(special_forms::set);
+        let lazy_cons = MankaiObject::SpecialForm(SpecialForm::Function(special_forms::lazy_cons));
+        environment.define(
+            &Token::new(String::from("lazy-cons!"), TokenKind::Identifier),
+            lazy_cons,
+        );
+
+        let let_ec = MankaiObject::SpecialForm(SpecialForm::Function(special_forms::let_ec));
+        environment.define(
+            &Token::new(String::from("let/ec!"), TokenKind::Identifier),
+            let_ec,
+        );
+
+        let let_special_form = MankaiObject::SpecialForm(SpecialForm::Let);
+        environment.define(
+            &Token::new(String::from("let!"), TokenKind::Identifier),
+            let_special_form,
+        );
+
+        let match_special_form = MankaiObject::SpecialForm(SpecialForm::Match);
+        environment.define(
+            &Token::new(String::from("match!"), TokenKind::Identifier),
+            match_special_form,
+        );
+
+        let parameterize =
+            MankaiObject::SpecialForm(SpecialForm::Function(special_forms::parameterize));
+        environment.define(
+            &Token::new(String::from("parameterize!"), TokenKind::Identifier),
+            parameterize,
+        );
+
+        let set = MankaiObject::SpecialForm(SpecialForm::Set);
         environment.define(
             &Token::new(String::from("set!"), TokenKind::Identifier),
             set,
@@ -84,12 +141,27 @@ impl Environment {
             greater_than,
         );
 
+        let eq = MankaiObject::Native(native_functions::eq);
+        environment.define(&Token::new(String::from("eq?"), TokenKind::Identifier), eq);
+
+        let equal = MankaiObject::Native(native_functions::equal);
+        environment.define(
+            &Token::new(String::from("equal?"), TokenKind::Identifier),
+            equal,
+        );
+
         let is_boolean = MankaiObject::Native(native_functions::is_boolean);
         environment.define(
             &Token::new(String::from("bool?"), TokenKind::Identifier),
             is_boolean,
         );
 
+        let is_keyword = MankaiObject::Native(native_functions::is_keyword);
+        environment.define(
+            &Token::new(String::from("keyword?"), TokenKind::Identifier),
+            is_keyword,
+        );
+
         let is_list = MankaiObject::Native(native_functions::is_list);
         environment.define(
             &Token::new(String::from("list?"), TokenKind::Identifier),
@@ -153,6 +225,749 @@ impl Environment {
             to_string,
         );
 
+        let write_to_string = MankaiObject::Native(native_functions::write_to_string);
+        environment.define(
+            &Token::new(String::from("write-to-string"), TokenKind::Identifier),
+            write_to_string,
+        );
+
+        let read_string = MankaiObject::Native(native_functions::read_string);
+        environment.define(
+            &Token::new(String::from("read-string"), TokenKind::Identifier),
+            read_string,
+        );
+
+        let any = MankaiObject::Native(native_functions::any);
+        environment.define(
+            &Token::new(String::from("any?"), TokenKind::Identifier),
+            any,
+        );
+
+        let apply = MankaiObject::Native(native_functions::apply);
+        environment.define(
+            &Token::new(String::from("apply"), TokenKind::Identifier),
+            apply,
+        );
+
+        let every = MankaiObject::Native(native_functions::every);
+        environment.define(
+            &Token::new(String::from("every?"), TokenKind::Identifier),
+            every,
+        );
+
+        let filter = MankaiObject::Native(native_functions::filter);
+        environment.define(
+            &Token::new(String::from("filter"), TokenKind::Identifier),
+            filter,
+        );
+
+        let find = MankaiObject::Native(native_functions::find);
+        environment.define(
+            &Token::new(String::from("find"), TokenKind::Identifier),
+            find,
+        );
+
+        let fold_left = MankaiObject::Native(native_functions::fold_left);
+        environment.define(
+            &Token::new(String::from("fold-left"), TokenKind::Identifier),
+            fold_left,
+        );
+
+        let fold_right = MankaiObject::Native(native_functions::fold_right);
+        environment.define(
+            &Token::new(String::from("fold-right"), TokenKind::Identifier),
+            fold_right,
+        );
+
+        let map = MankaiObject::Native(native_functions::map);
+        environment.define(&Token::new(String::from("map"), TokenKind::Identifier), map);
+
+        let reduce = MankaiObject::Native(native_functions::reduce);
+        environment.define(
+            &Token::new(String::from("reduce"), TokenKind::Identifier),
+            reduce,
+        );
+
+        let sort_by = MankaiObject::Native(native_functions::sort_by);
+        environment.define(
+            &Token::new(String::from("sort-by"), TokenKind::Identifier),
+            sort_by,
+        );
+
+        let append = MankaiObject::Native(native_functions::append);
+        environment.define(
+            &Token::new(String::from("append"), TokenKind::Identifier),
+            append,
+        );
+
+        let drop = MankaiObject::Native(native_functions::drop);
+        environment.define(
+            &Token::new(String::from("drop"), TokenKind::Identifier),
+            drop,
+        );
+
+        let flatten = MankaiObject::Native(native_functions::flatten);
+        environment.define(
+            &Token::new(String::from("flatten"), TokenKind::Identifier),
+            flatten,
+        );
+
+        let index_of = MankaiObject::Native(native_functions::index_of);
+        environment.define(
+            &Token::new(String::from("index-of"), TokenKind::Identifier),
+            index_of,
+        );
+
+        let last = MankaiObject::Native(native_functions::last);
+        environment.define(
+            &Token::new(String::from("last"), TokenKind::Identifier),
+            last,
+        );
+
+        let length = MankaiObject::Native(native_functions::length);
+        environment.define(
+            &Token::new(String::from("length"), TokenKind::Identifier),
+            length,
+        );
+
+        let is_member = MankaiObject::Native(native_functions::is_member);
+        environment.define(
+            &Token::new(String::from("member?"), TokenKind::Identifier),
+            is_member,
+        );
+
+        let nth = MankaiObject::Native(native_functions::nth);
+        environment.define(&Token::new(String::from("nth"), TokenKind::Identifier), nth);
+
+        let range = MankaiObject::Native(native_functions::range);
+        environment.define(
+            &Token::new(String::from("range"), TokenKind::Identifier),
+            range,
+        );
+
+        let reverse = MankaiObject::Native(native_functions::reverse);
+        environment.define(
+            &Token::new(String::from("reverse"), TokenKind::Identifier),
+            reverse,
+        );
+
+        let sort = MankaiObject::Native(native_functions::sort);
+        environment.define(
+            &Token::new(String::from("sort"), TokenKind::Identifier),
+            sort,
+        );
+
+        let take = MankaiObject::Native(native_functions::take);
+        environment.define(
+            &Token::new(String::from("take"), TokenKind::Identifier),
+            take,
+        );
+
+        let unique = MankaiObject::Native(native_functions::unique);
+        environment.define(
+            &Token::new(String::from("unique"), TokenKind::Identifier),
+            unique,
+        );
+
+        let zip = MankaiObject::Native(native_functions::zip);
+        environment.define(&Token::new(String::from("zip"), TokenKind::Identifier), zip);
+
+        let list_to_string = MankaiObject::Native(native_functions::list_to_string);
+        environment.define(
+            &Token::new(String::from("list->string"), TokenKind::Identifier),
+            list_to_string,
+        );
+
+        let string_to_list = MankaiObject::Native(native_functions::string_to_list);
+        environment.define(
+            &Token::new(String::from("string->list"), TokenKind::Identifier),
+            string_to_list,
+        );
+
+        let string_to_number = MankaiObject::Native(native_functions::string_to_number);
+        environment.define(
+            &Token::new(String::from("string->number"), TokenKind::Identifier),
+            string_to_number,
+        );
+
+        let string_contains = MankaiObject::Native(native_functions::string_contains);
+        environment.define(
+            &Token::new(String::from("string-contains?"), TokenKind::Identifier),
+            string_contains,
+        );
+
+        let string_downcase = MankaiObject::Native(native_functions::string_downcase);
+        environment.define(
+            &Token::new(String::from("string-downcase"), TokenKind::Identifier),
+            string_downcase,
+        );
+
+        let string_ends_with = MankaiObject::Native(native_functions::string_ends_with);
+        environment.define(
+            &Token::new(String::from("string-ends-with?"), TokenKind::Identifier),
+            string_ends_with,
+        );
+
+        let string_index = MankaiObject::Native(native_functions::string_index);
+        environment.define(
+            &Token::new(String::from("string-index"), TokenKind::Identifier),
+            string_index,
+        );
+
+        let string_join = MankaiObject::Native(native_functions::string_join);
+        environment.define(
+            &Token::new(String::from("string-join"), TokenKind::Identifier),
+            string_join,
+        );
+
+        let string_length = MankaiObject::Native(native_functions::string_length);
+        environment.define(
+            &Token::new(String::from("string-length"), TokenKind::Identifier),
+            string_length,
+        );
+
+        let string_replace = MankaiObject::Native(native_functions::string_replace);
+        environment.define(
+            &Token::new(String::from("string-replace"), TokenKind::Identifier),
+            string_replace,
+        );
+
+        let string_reverse = MankaiObject::Native(native_functions::string_reverse);
+        environment.define(
+            &Token::new(String::from("string-reverse"), TokenKind::Identifier),
+            string_reverse,
+        );
+
+        let string_split = MankaiObject::Native(native_functions::string_split);
+        environment.define(
+            &Token::new(String::from("string-split"), TokenKind::Identifier),
+            string_split,
+        );
+
+        let string_starts_with = MankaiObject::Native(native_functions::string_starts_with);
+        environment.define(
+            &Token::new(String::from("string-starts-with?"), TokenKind::Identifier),
+            string_starts_with,
+        );
+
+        let string_trim = MankaiObject::Native(native_functions::string_trim);
+        environment.define(
+            &Token::new(String::from("string-trim"), TokenKind::Identifier),
+            string_trim,
+        );
+
+        let string_upcase = MankaiObject::Native(native_functions::string_upcase);
+        environment.define(
+            &Token::new(String::from("string-upcase"), TokenKind::Identifier),
+            string_upcase,
+        );
+
+        let substring = MankaiObject::Native(native_functions::substring);
+        environment.define(
+            &Token::new(String::from("substring"), TokenKind::Identifier),
+            substring,
+        );
+
+        let abs = MankaiObject::Native(native_functions::abs);
+        environment.define(&Token::new(String::from("abs"), TokenKind::Identifier), abs);
+
+        let acos = MankaiObject::Native(native_functions::acos);
+        environment.define(
+            &Token::new(String::from("acos"), TokenKind::Identifier),
+            acos,
+        );
+
+        let asin = MankaiObject::Native(native_functions::asin);
+        environment.define(
+            &Token::new(String::from("asin"), TokenKind::Identifier),
+            asin,
+        );
+
+        let atan = MankaiObject::Native(native_functions::atan);
+        environment.define(
+            &Token::new(String::from("atan"), TokenKind::Identifier),
+            atan,
+        );
+
+        let atan2 = MankaiObject::Native(native_functions::atan2);
+        environment.define(
+            &Token::new(String::from("atan2"), TokenKind::Identifier),
+            atan2,
+        );
+
+        let ceil = MankaiObject::Native(native_functions::ceil);
+        environment.define(
+            &Token::new(String::from("ceil"), TokenKind::Identifier),
+            ceil,
+        );
+
+        let cos = MankaiObject::Native(native_functions::cos);
+        environment.define(&Token::new(String::from("cos"), TokenKind::Identifier), cos);
+
+        let exp = MankaiObject::Native(native_functions::exp);
+        environment.define(&Token::new(String::from("exp"), TokenKind::Identifier), exp);
+
+        let floor = MankaiObject::Native(native_functions::floor);
+        environment.define(
+            &Token::new(String::from("floor"), TokenKind::Identifier),
+            floor,
+        );
+
+        let log = MankaiObject::Native(native_functions::log);
+        environment.define(&Token::new(String::from("log"), TokenKind::Identifier), log);
+
+        let max = MankaiObject::Native(native_functions::max);
+        environment.define(&Token::new(String::from("max"), TokenKind::Identifier), max);
+
+        let min = MankaiObject::Native(native_functions::min);
+        environment.define(&Token::new(String::from("min"), TokenKind::Identifier), min);
+
+        let modulo = MankaiObject::Native(native_functions::modulo);
+        environment.define(
+            &Token::new(String::from("mod"), TokenKind::Identifier),
+            modulo,
+        );
+
+        let pow = MankaiObject::Native(native_functions::pow);
+        environment.define(&Token::new(String::from("pow"), TokenKind::Identifier), pow);
+
+        let quotient = MankaiObject::Native(native_functions::quotient);
+        environment.define(
+            &Token::new(String::from("quotient"), TokenKind::Identifier),
+            quotient,
+        );
+
+        let remainder = MankaiObject::Native(native_functions::remainder);
+        environment.define(
+            &Token::new(String::from("remainder"), TokenKind::Identifier),
+            remainder,
+        );
+
+        let round = MankaiObject::Native(native_functions::round);
+        environment.define(
+            &Token::new(String::from("round"), TokenKind::Identifier),
+            round,
+        );
+
+        let sin = MankaiObject::Native(native_functions::sin);
+        environment.define(&Token::new(String::from("sin"), TokenKind::Identifier), sin);
+
+        let sqrt = MankaiObject::Native(native_functions::sqrt);
+        environment.define(
+            &Token::new(String::from("sqrt"), TokenKind::Identifier),
+            sqrt,
+        );
+
+        let tan = MankaiObject::Native(native_functions::tan);
+        environment.define(&Token::new(String::from("tan"), TokenKind::Identifier), tan);
+
+        let truncate = MankaiObject::Native(native_functions::truncate);
+        environment.define(
+            &Token::new(String::from("truncate"), TokenKind::Identifier),
+            truncate,
+        );
+
+        // Bring to scope the vector functions.
+        let vector = MankaiObject::Native(native_functions::vector);
+        environment.define(
+            &Token::new(String::from("vector"), TokenKind::Identifier),
+            vector,
+        );
+
+        let is_vector = MankaiObject::Native(native_functions::is_vector);
+        environment.define(
+            &Token::new(String::from("vector?"), TokenKind::Identifier),
+            is_vector,
+        );
+
+        let vector_ref = MankaiObject::Native(native_functions::vector_ref);
+        environment.define(
+            &Token::new(String::from("vector-ref"), TokenKind::Identifier),
+            vector_ref,
+        );
+
+        let vector_set = MankaiObject::Native(native_functions::vector_set);
+        environment.define(
+            &Token::new(String::from("vector-set"), TokenKind::Identifier),
+            vector_set,
+        );
+
+        let vector_length = MankaiObject::Native(native_functions::vector_length);
+        environment.define(
+            &Token::new(String::from("vector-length"), TokenKind::Identifier),
+            vector_length,
+        );
+
+        let vector_to_list = MankaiObject::Native(native_functions::vector_to_list);
+        environment.define(
+            &Token::new(String::from("vector->list"), TokenKind::Identifier),
+            vector_to_list,
+        );
+
+        let list_to_vector = MankaiObject::Native(native_functions::list_to_vector);
+        environment.define(
+            &Token::new(String::from("list->vector"), TokenKind::Identifier),
+            list_to_vector,
+        );
+
+        // Bring to scope the map functions.
+        let hash_map = MankaiObject::Native(native_functions::hash_map);
+        environment.define(
+            &Token::new(String::from("hash-map"), TokenKind::Identifier),
+            hash_map,
+        );
+
+        let is_map = MankaiObject::Native(native_functions::is_map);
+        environment.define(
+            &Token::new(String::from("map?"), TokenKind::Identifier),
+            is_map,
+        );
+
+        let get = MankaiObject::Native(native_functions::get);
+        environment.define(&Token::new(String::from("get"), TokenKind::Identifier), get);
+
+        let assoc = MankaiObject::Native(native_functions::assoc);
+        environment.define(
+            &Token::new(String::from("assoc"), TokenKind::Identifier),
+            assoc,
+        );
+
+        let dissoc = MankaiObject::Native(native_functions::dissoc);
+        environment.define(
+            &Token::new(String::from("dissoc"), TokenKind::Identifier),
+            dissoc,
+        );
+
+        let contains_key = MankaiObject::Native(native_functions::contains_key);
+        environment.define(
+            &Token::new(String::from("contains-key?"), TokenKind::Identifier),
+            contains_key,
+        );
+
+        let keys = MankaiObject::Native(native_functions::keys);
+        environment.define(
+            &Token::new(String::from("keys"), TokenKind::Identifier),
+            keys,
+        );
+
+        let values = MankaiObject::Native(native_functions::values);
+        environment.define(
+            &Token::new(String::from("values"), TokenKind::Identifier),
+            values,
+        );
+
+        let map_merge = MankaiObject::Native(native_functions::map_merge);
+        environment.define(
+            &Token::new(String::from("map-merge"), TokenKind::Identifier),
+            map_merge,
+        );
+
+        // Bring to scope the set functions.
+        let set = MankaiObject::Native(native_functions::set);
+        environment.define(&Token::new(String::from("set"), TokenKind::Identifier), set);
+
+        let is_set = MankaiObject::Native(native_functions::is_set);
+        environment.define(
+            &Token::new(String::from("set?"), TokenKind::Identifier),
+            is_set,
+        );
+
+        let set_add = MankaiObject::Native(native_functions::set_add);
+        environment.define(
+            &Token::new(String::from("set-add"), TokenKind::Identifier),
+            set_add,
+        );
+
+        let set_remove = MankaiObject::Native(native_functions::set_remove);
+        environment.define(
+            &Token::new(String::from("set-remove"), TokenKind::Identifier),
+            set_remove,
+        );
+
+        let set_member = MankaiObject::Native(native_functions::set_member);
+        environment.define(
+            &Token::new(String::from("set-member?"), TokenKind::Identifier),
+            set_member,
+        );
+
+        let union = MankaiObject::Native(native_functions::union);
+        environment.define(
+            &Token::new(String::from("union"), TokenKind::Identifier),
+            union,
+        );
+
+        let intersection = MankaiObject::Native(native_functions::intersection);
+        environment.define(
+            &Token::new(String::from("intersection"), TokenKind::Identifier),
+            intersection,
+        );
+
+        let difference = MankaiObject::Native(native_functions::difference);
+        environment.define(
+            &Token::new(String::from("difference"), TokenKind::Identifier),
+            difference,
+        );
+
+        let is_subset = MankaiObject::Native(native_functions::is_subset);
+        environment.define(
+            &Token::new(String::from("subset?"), TokenKind::Identifier),
+            is_subset,
+        );
+
+        let set_to_list = MankaiObject::Native(native_functions::set_to_list);
+        environment.define(
+            &Token::new(String::from("set->list"), TokenKind::Identifier),
+            set_to_list,
+        );
+
+        // Bring to scope the lazy functions.
+        let force = MankaiObject::Native(native_functions::force);
+        environment.define(
+            &Token::new(String::from("force"), TokenKind::Identifier),
+            force,
+        );
+
+        let make_promise = MankaiObject::Native(native_functions::make_promise);
+        environment.define(
+            &Token::new(String::from("make-promise"), TokenKind::Identifier),
+            make_promise,
+        );
+
+        let is_promise = MankaiObject::Native(native_functions::is_promise);
+        environment.define(
+            &Token::new(String::from("promise?"), TokenKind::Identifier),
+            is_promise,
+        );
+
+        let iterate = MankaiObject::Native(native_functions::iterate);
+        environment.define(
+            &Token::new(String::from("iterate"), TokenKind::Identifier),
+            iterate,
+        );
+
+        let lazy_map = MankaiObject::Native(native_functions::lazy_map);
+        environment.define(
+            &Token::new(String::from("lazy-map"), TokenKind::Identifier),
+            lazy_map,
+        );
+
+        let lazy_filter = MankaiObject::Native(native_functions::lazy_filter);
+        environment.define(
+            &Token::new(String::from("lazy-filter"), TokenKind::Identifier),
+            lazy_filter,
+        );
+
+        let is_lazy_seq = MankaiObject::Native(native_functions::is_lazy_seq);
+        environment.define(
+            &Token::new(String::from("lazy-seq?"), TokenKind::Identifier),
+            is_lazy_seq,
+        );
+
+        // Bring to scope the generator functions.
+        let make_generator = MankaiObject::Native(native_functions::make_generator);
+        environment.define(
+            &Token::new(String::from("make-generator"), TokenKind::Identifier),
+            make_generator,
+        );
+
+        let next = MankaiObject::Native(native_functions::next);
+        environment.define(
+            &Token::new(String::from("next"), TokenKind::Identifier),
+            next,
+        );
+
+        let generator_done = MankaiObject::Native(native_functions::generator_done);
+        environment.define(
+            &Token::new(String::from("generator-done?"), TokenKind::Identifier),
+            generator_done,
+        );
+
+        let is_generator = MankaiObject::Native(native_functions::is_generator);
+        environment.define(
+            &Token::new(String::from("generator?"), TokenKind::Identifier),
+            is_generator,
+        );
+
+        let yield_value = MankaiObject::SpecialForm(SpecialForm::Yield);
+        environment.define(
+            &Token::new(String::from("yield"), TokenKind::Identifier),
+            yield_value,
+        );
+
+        // Bring to scope the continuation functions.
+        let call_cc = MankaiObject::SpecialForm(SpecialForm::CallCc);
+        environment.define(
+            &Token::new(String::from("call/cc"), TokenKind::Identifier),
+            call_cc,
+        );
+
+        // Bring to scope the parameter functions.
+        let make_parameter = MankaiObject::Native(native_functions::make_parameter);
+        environment.define(
+            &Token::new(String::from("make-parameter"), TokenKind::Identifier),
+            make_parameter,
+        );
+
+        let is_parameter = MankaiObject::Native(native_functions::is_parameter);
+        environment.define(
+            &Token::new(String::from("parameter?"), TokenKind::Identifier),
+            is_parameter,
+        );
+
+        // Bring to scope the box functions.
+        let make_box = MankaiObject::Native(native_functions::make_box);
+        environment.define(
+            &Token::new(String::from("box"), TokenKind::Identifier),
+            make_box,
+        );
+
+        let unbox = MankaiObject::Native(native_functions::unbox);
+        environment.define(
+            &Token::new(String::from("unbox"), TokenKind::Identifier),
+            unbox,
+        );
+
+        let set_box = MankaiObject::Native(native_functions::set_box);
+        environment.define(
+            &Token::new(String::from("set-box!"), TokenKind::Identifier),
+            set_box,
+        );
+
+        let is_box = MankaiObject::Native(native_functions::is_box);
+        environment.define(
+            &Token::new(String::from("box?"), TokenKind::Identifier),
+            is_box,
+        );
+
+        // Bring to scope the atom functions.
+        let atom = MankaiObject::Native(native_functions::atom);
+        environment.define(
+            &Token::new(String::from("atom"), TokenKind::Identifier),
+            atom,
+        );
+
+        let deref = MankaiObject::Native(native_functions::deref);
+        environment.define(
+            &Token::new(String::from("deref"), TokenKind::Identifier),
+            deref,
+        );
+
+        let swap = MankaiObject::Native(native_functions::swap);
+        environment.define(
+            &Token::new(String::from("swap!"), TokenKind::Identifier),
+            swap,
+        );
+
+        let reset = MankaiObject::Native(native_functions::reset);
+        environment.define(
+            &Token::new(String::from("reset!"), TokenKind::Identifier),
+            reset,
+        );
+
+        let is_atom = MankaiObject::Native(native_functions::is_atom);
+        environment.define(
+            &Token::new(String::from("atom?"), TokenKind::Identifier),
+            is_atom,
+        );
+
+        // Bring to scope the character functions.
+        let is_char = MankaiObject::Native(native_functions::is_char);
+        environment.define(
+            &Token::new(String::from("char?"), TokenKind::Identifier),
+            is_char,
+        );
+
+        let is_char_alphabetic = MankaiObject::Native(native_functions::is_char_alphabetic);
+        environment.define(
+            &Token::new(String::from("char-alphabetic?"), TokenKind::Identifier),
+            is_char_alphabetic,
+        );
+
+        let is_char_numeric = MankaiObject::Native(native_functions::is_char_numeric);
+        environment.define(
+            &Token::new(String::from("char-numeric?"), TokenKind::Identifier),
+            is_char_numeric,
+        );
+
+        let is_char_whitespace = MankaiObject::Native(native_functions::is_char_whitespace);
+        environment.define(
+            &Token::new(String::from("char-whitespace?"), TokenKind::Identifier),
+            is_char_whitespace,
+        );
+
+        let is_char_upper_case = MankaiObject::Native(native_functions::is_char_upper_case);
+        environment.define(
+            &Token::new(String::from("char-upper-case?"), TokenKind::Identifier),
+            is_char_upper_case,
+        );
+
+        let is_char_lower_case = MankaiObject::Native(native_functions::is_char_lower_case);
+        environment.define(
+            &Token::new(String::from("char-lower-case?"), TokenKind::Identifier),
+            is_char_lower_case,
+        );
+
+        let char_upcase = MankaiObject::Native(native_functions::char_upcase);
+        environment.define(
+            &Token::new(String::from("char-upcase"), TokenKind::Identifier),
+            char_upcase,
+        );
+
+        let char_downcase = MankaiObject::Native(native_functions::char_downcase);
+        environment.define(
+            &Token::new(String::from("char-downcase"), TokenKind::Identifier),
+            char_downcase,
+        );
+
+        let char_to_integer = MankaiObject::Native(native_functions::char_to_integer);
+        environment.define(
+            &Token::new(String::from("char->integer"), TokenKind::Identifier),
+            char_to_integer,
+        );
+
+        let integer_to_char = MankaiObject::Native(native_functions::integer_to_char);
+        environment.define(
+            &Token::new(String::from("integer->char"), TokenKind::Identifier),
+            integer_to_char,
+        );
+
+        let char_to_string = MankaiObject::Native(native_functions::char_to_string);
+        environment.define(
+            &Token::new(String::from("char->string"), TokenKind::Identifier),
+            char_to_string,
+        );
+
+        // Bring to scope the numeric tower functions.
+        let exact = MankaiObject::Native(native_functions::exact);
+        environment.define(
+            &Token::new(String::from("exact"), TokenKind::Identifier),
+            exact,
+        );
+
+        let inexact = MankaiObject::Native(native_functions::inexact);
+        environment.define(
+            &Token::new(String::from("inexact"), TokenKind::Identifier),
+            inexact,
+        );
+
+        let is_exact = MankaiObject::Native(native_functions::is_exact);
+        environment.define(
+            &Token::new(String::from("exact?"), TokenKind::Identifier),
+            is_exact,
+        );
+
+        let is_inexact = MankaiObject::Native(native_functions::is_inexact);
+        environment.define(
+            &Token::new(String::from("inexact?"), TokenKind::Identifier),
+            is_inexact,
+        );
+
+        let is_integer = MankaiObject::Native(native_functions::is_integer);
+        environment.define(
+            &Token::new(String::from("integer?"), TokenKind::Identifier),
+            is_integer,
+        );
+
         // Bring to scope some constants.
         environment.define(
             &Token::new(String::from("true"), TokenKind::Identifier),
@@ -164,6 +979,16 @@ impl Environment {
             MankaiObject::Bool(false),
         );
 
+        environment.define(
+            &Token::new(String::from("pi"), TokenKind::Identifier),
+            MankaiObject::Number(Number::Float(std::f64::consts::PI)),
+        );
+
+        environment.define(
+            &Token::new(String::from("e"), TokenKind::Identifier),
+            MankaiObject::Number(Number::Float(std::f64::consts::E)),
+        );
+
         environment
     }
 
@@ -214,6 +1039,44 @@ impl Environment {
         self.layers.push(HashMap::new());
     }
 
+    /// Extend the environment with a new layer holding some bindings.
+    pub fn extend_with(&mut self, bindings: &HashMap<String, MankaiObject>) {
+        self.layers.push(bindings.clone());
+    }
+
+    /// All the bindings but the global ones, as seen from the last layer.
+    pub fn locals(&self) -> HashMap<String, MankaiObject> {
+        let mut locals = HashMap::new();
+        for layer in self.layers.iter().skip(1) {
+            for (name, value) in layer {
+                locals.insert(name.clone(), value.clone());
+            }
+        }
+
+        locals
+    }
+
+    /// Number of layers, including the global one.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Remove the layers above the given depth and return them, to put them
+    /// back later with `restore`.
+    pub fn split_off(&mut self, depth: usize) -> Vec<HashMap<String, MankaiObject>> {
+        self.layers.split_off(depth.max(1))
+    }
+
+    /// Copy the layers above the given depth.
+    pub fn copy_layers(&self, depth: usize) -> Vec<HashMap<String, MankaiObject>> {
+        self.layers[depth.max(1)..].to_vec()
+    }
+
+    /// Put back on top of the environment layers removed by `split_off`.
+    pub fn restore(&mut self, layers: Vec<HashMap<String, MankaiObject>>) {
+        self.layers.extend(layers);
+    }
+
     /// Remove the last layer of the environment (panics if trying to remove the
     /// global scope).
     pub fn restrict(&mut self) {
@@ -229,6 +1092,7 @@ impl Environment {
 mod environment_test {
     use super::Environment;
     use crate::interpreter::MankaiObject;
+    use crate::number::Number;
     use crate::token::*;
 
     #[test]
@@ -238,22 +1102,22 @@ mod environment_test {
         // Define a couple of bindings.
         environment.define(
             &Token::new(String::from("foo"), TokenKind::Identifier),
-            MankaiObject::Number(6.0),
+            MankaiObject::Number(Number::from(6)),
         );
 
         environment.define(
             &Token::new(String::from("bar"), TokenKind::Identifier),
-            MankaiObject::String(String::from("baz")),
+            MankaiObject::String("baz".into()),
         );
 
         // Try to get them out and test runtime errors.
         match environment.get(&Token::new(String::from("foo"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::Number(6.0)),
+            Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(6))),
             Err(err) => panic!(err.message),
         }
 
         match environment.get(&Token::new(String::from("bar"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::String(String::from("baz"))),
+            Ok(value) => assert_eq!(value, MankaiObject::String("baz".into())),
             Err(err) => panic!(err.message),
         }
 
@@ -269,12 +1133,12 @@ mod environment_test {
         // Put something in the global scope.
         environment.define(
             &Token::new(String::from("foo"), TokenKind::Identifier),
-            MankaiObject::Number(6.0),
+            MankaiObject::Number(Number::from(6)),
         );
 
         environment.define(
             &Token::new(String::from("bar"), TokenKind::Identifier),
-            MankaiObject::String(String::from("baz")),
+            MankaiObject::String("baz".into()),
         );
 
         // Extend the environment and define something again.
@@ -282,27 +1146,27 @@ mod environment_test {
 
         environment.define(
             &Token::new(String::from("foo"), TokenKind::Identifier),
-            MankaiObject::Number(12.0),
+            MankaiObject::Number(Number::from(12)),
         );
 
         environment.define(
             &Token::new(String::from("baz"), TokenKind::Identifier),
-            MankaiObject::Number(0.0),
+            MankaiObject::Number(Number::from(0)),
         );
 
         // Check that the extended environment acts properly.
         match environment.get(&Token::new(String::from("foo"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::Number(12.0)),
+            Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(12))),
             Err(err) => panic!(err.message),
         }
 
         match environment.get(&Token::new(String::from("bar"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::String(String::from("baz"))),
+            Ok(value) => assert_eq!(value, MankaiObject::String("baz".into())),
             Err(err) => panic!(err.message),
         }
 
         match environment.get(&Token::new(String::from("baz"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::Number(0.0)),
+            Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(0))),
             Err(err) => panic!(err.message),
         }
 
@@ -311,12 +1175,12 @@ mod environment_test {
 
         // Check that the restricted environment acts properly.
         match environment.get(&Token::new(String::from("foo"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::Number(6.0)),
+            Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(6))),
             Err(err) => panic!(err.message),
         }
 
         match environment.get(&Token::new(String::from("bar"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::String(String::from("baz"))),
+            Ok(value) => assert_eq!(value, MankaiObject::String("baz".into())),
             Err(err) => panic!(err.message),
         }
     }
@@ -328,7 +1192,7 @@ mod environment_test {
         // Put something in the environment.
         environment.define(
             &Token::new(String::from("foo"), TokenKind::Identifier),
-            MankaiObject::Number(6.0),
+            MankaiObject::Number(Number::from(6)),
         );
 
         // Extend the environment, set something again.
@@ -336,13 +1200,13 @@ mod environment_test {
 
         if let Err(err) = environment.set(
             &Token::new(String::from("foo"), TokenKind::Identifier),
-            MankaiObject::Number(7.0),
+            MankaiObject::Number(Number::from(7)),
         ) {
             panic!(err.message);
         }
 
         match environment.get(&Token::new(String::from("foo"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::Number(7.0)),
+            Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(7))),
             Err(err) => panic!(err.message),
         }
 
@@ -350,14 +1214,14 @@ mod environment_test {
         environment.restrict();
 
         match environment.get(&Token::new(String::from("foo"), TokenKind::Identifier)) {
-            Ok(value) => assert_eq!(value, MankaiObject::Number(7.0)),
+            Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(7))),
             Err(err) => panic!(err.message),
         }
 
         // Check that the runtime error for the set method works.
         if let Ok(()) = environment.set(
             &Token::new(String::from("bar"), TokenKind::Identifier),
-            MankaiObject::Number(3.0),
+            MankaiObject::Number(Number::from(3)),
         ) {
             panic!("expected runtime error. Cannot assign to an undefined symbol!");
         }
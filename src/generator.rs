@@ -0,0 +1,92 @@
+use crate::interpreter::{Interpreter, MankaiObject, RuntimeError};
+use crate::machine::{Outcome, Suspension};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+enum GeneratorState {
+    /// Not started yet: the function to call.
+    Created(MankaiObject),
+    /// Suspended by `yield`.
+    Suspended(Suspension),
+    /// Running, between a call to `next` and the `yield` or the return ending
+    /// it.
+    Running,
+    /// Finished, by returning or with an error.
+    Done,
+}
+
+/// A generator made by `make-generator`: a function whose evaluation is
+/// suspended each time it calls `yield`, and resumed by `next`.
+#[derive(Clone)]
+pub struct Generator(Rc<RefCell<GeneratorState>>);
+
+impl Generator {
+    /// Make a generator running a function without arguments.
+    pub fn new(function: MankaiObject) -> Self {
+        Generator(Rc::new(RefCell::new(GeneratorState::Created(function))))
+    }
+
+    /// Check if the generator has finished.
+    pub fn is_done(&self) -> bool {
+        matches!(*self.0.borrow(), GeneratorState::Done)
+    }
+
+    /// Run the generator until it yields a value or returns, and get that
+    /// value. `value` becomes the value of the call to `yield` which suspended
+    /// the generator (it's ignored when starting the generator).
+    pub fn resume(
+        &self,
+        interpreter: &mut Interpreter,
+        value: MankaiObject,
+    ) -> Result<MankaiObject, RuntimeError> {
+        let state = self.0.replace(GeneratorState::Running);
+        let outcome = match state {
+            GeneratorState::Created(function) => interpreter.start(&function),
+            GeneratorState::Suspended(suspension) => interpreter.resume(suspension, value),
+            GeneratorState::Running => {
+                return Err(RuntimeError::new(
+                    "can't resume a generator from its own body!",
+                ))
+            }
+            GeneratorState::Done => {
+                self.0.replace(GeneratorState::Done);
+                return Err(RuntimeError::new("can't resume a finished generator!"));
+            }
+        };
+
+        match outcome {
+            Ok(Outcome::Yielded(value, suspension)) => {
+                self.0.replace(GeneratorState::Suspended(suspension));
+                Ok(value)
+            }
+            Ok(Outcome::Finished(value)) => {
+                self.0.replace(GeneratorState::Done);
+                Ok(value)
+            }
+            Err(err) => {
+                self.0.replace(GeneratorState::Done);
+                Err(err)
+            }
+        }
+    }
+
+    /// Check if two generators are the same object.
+    pub fn ptr_eq(&self, other: &Generator) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Address of the generator, identifying it.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+}
+
+impl std::fmt::Display for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_done() {
+            write!(f, "<generator done>")
+        } else {
+            write!(f, "<generator>")
+        }
+    }
+}
@@ -1,34 +1,123 @@
+use crate::dynamic::Parameter;
 use crate::environment::Environment;
+use crate::generator::Generator;
+use crate::lazy::{LazySeq, Promise};
+use crate::list::List;
+use crate::machine::{Continuation, Jump};
+use crate::map::Map;
+use crate::native_functions;
+use crate::number::Number;
+use crate::parameters::{describe_arities, Parameters};
 use crate::parser::Sexp;
+use crate::record::{Record, RecordFunction};
+use crate::reference::Reference;
+use crate::set::Set;
 use crate::token::*;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
 /// A runtime error.
 pub struct RuntimeError {
     /// Error message.
     pub message: String,
+    /// Set when the error is a continuation being called instead, making its
+    /// way out of the native functions and evaluations it's called from.
+    pub(crate) jump: Option<Box<Jump>>,
 }
 
 impl RuntimeError {
     pub fn new(message: &str) -> Self {
         RuntimeError {
             message: String::from(message),
+            jump: None,
+        }
+    }
+}
+
+/// A clause of a user-defined function: a parameter list and the body to
+/// evaluate when a call matches it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Clause {
+    /// Parameters of the clause.
+    pub parameters: Parameters,
+    /// Body of the clause.
+    pub body: Sexp,
+}
+
+/// A special form, called with its arguments unevaluated, or a function
+/// which only the evaluator can run fully.
+#[derive(Clone, Copy)]
+pub enum SpecialForm {
+    /// `define!`.
+    Define,
+    /// `if!`.
+    If,
+    /// `let!`.
+    Let,
+    /// `match!`.
+    Match,
+    /// `set!`.
+    Set,
+    /// `yield`, a native function for Mankai code, whose calls suspend the
+    /// generator running.
+    Yield,
+    /// `call/cc`, a native function for Mankai code, whose calls capture the
+    /// rest of the evaluation.
+    CallCc,
+    /// Any other special form, implemented by a function of the arguments.
+    /// The forms above are run by the evaluator itself, so that `yield` can
+    /// suspend a generator in the middle of them.
+    Function(fn(&mut Interpreter, Vec<&Sexp>) -> Result<MankaiObject, RuntimeError>),
+}
+
+impl SpecialForm {
+    /// Address identifying the special form: the address of its function, or
+    /// its position among the forms run by the evaluator.
+    fn address(&self) -> usize {
+        match self {
+            SpecialForm::Define => 1,
+            SpecialForm::If => 2,
+            SpecialForm::Let => 3,
+            SpecialForm::Match => 4,
+            SpecialForm::Set => 5,
+            SpecialForm::Yield => 6,
+            SpecialForm::CallCc => 7,
+            SpecialForm::Function(function) => *function as usize,
         }
     }
 }
 
 #[derive(Clone)]
 pub enum MankaiObject {
-    Number(f64),
-    String(String),
-    List(Vec<MankaiObject>),
+    Number(Number),
+    String(Rc<str>),
+    List(List),
     Bool(bool),
-    SpecialForm(fn(&mut Interpreter, Vec<&Sexp>) -> Result<MankaiObject, RuntimeError>),
-    Native(fn(Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError>),
+    Keyword(String),
+    Char(char),
+    Map(Map),
+    Set(Set),
+    Vector(Rc<Vec<MankaiObject>>),
+    Record(Record),
+    Promise(Promise),
+    LazySeq(LazySeq),
+    Generator(Generator),
+    Continuation(Continuation),
+    Parameter(Parameter),
+    Box(Reference),
+    Atom(Reference),
+    SpecialForm(SpecialForm),
+    Native(fn(&mut Interpreter, Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError>),
     Function {
-        name: Option<String>,
-        arguments_identifiers: Vec<Token>,
-        body: Sexp,
+        name: Option<Rc<str>>,
+        clauses: Rc<Vec<Clause>>,
+        /// Local bindings visible where the function was made.
+        captured: Rc<HashMap<String, MankaiObject>>,
     },
+    RecordFunction(RecordFunction),
 }
 
 impl std::fmt::Debug for MankaiObject {
@@ -48,18 +137,68 @@ impl std::fmt::Debug for MankaiObject {
             }
             MankaiObject::Bool(true) => write!(f, "true"),
             MankaiObject::Bool(false) => write!(f, "false"),
+            MankaiObject::Keyword(k) => write!(f, ":{}", k),
+            MankaiObject::Char(c) => write!(f, "{}", c),
+            MankaiObject::Map(map) => {
+                write!(f, "{{ ")?;
+
+                for (key, value) in map.iter() {
+                    key.fmt(f)?;
+                    write!(f, " ")?;
+                    value.fmt(f)?;
+                    write!(f, " ")?;
+                }
+
+                write!(f, " }}")
+            }
+            MankaiObject::Set(set) => {
+                write!(f, "#{{ ")?;
+
+                for elem in set.iter() {
+                    elem.fmt(f)?;
+                    write!(f, " ")?;
+                }
+
+                write!(f, " }}")
+            }
+            MankaiObject::Vector(vector) => {
+                write!(f, "[ ")?;
+
+                for elem in vector.iter() {
+                    elem.fmt(f)?;
+                    write!(f, " ")?;
+                }
+
+                write!(f, " ]")
+            }
+            MankaiObject::Record(record) => write!(f, "{}", record),
+            MankaiObject::Promise(promise) => write!(f, "{}", promise),
+            MankaiObject::LazySeq(sequence) => write!(f, "{}", sequence),
+            MankaiObject::Generator(generator) => write!(f, "{}", generator),
+            MankaiObject::Continuation(_) => write!(f, "continuation"),
+            MankaiObject::Parameter(_) => write!(f, "parameter"),
+            MankaiObject::Box(reference) => write!(f, "{}", reference.to_string("box")),
+            MankaiObject::Atom(reference) => write!(f, "{}", reference.to_string("atom")),
+            MankaiObject::SpecialForm(SpecialForm::Yield | SpecialForm::CallCc) => {
+                write!(f, "native function")
+            }
             MankaiObject::SpecialForm(_) => write!(f, "special form"),
             MankaiObject::Native(_) => write!(f, "native function"),
             MankaiObject::Function { .. } => write!(f, "user-defined function"),
+            MankaiObject::RecordFunction(_) => write!(f, "record function"),
         }
     }
 }
 
 impl PartialEq for MankaiObject {
+    /// Structural equality, as checked by `equal?`: numbers are equal if they
+    /// have the same value (and NaN is equal to itself), collections and
+    /// records if they have equal contents, while functions and special forms
+    /// are only equal to themselves.
     fn eq(&self, other: &Self) -> bool {
         match self {
             MankaiObject::Number(n) => match other {
-                MankaiObject::Number(m) => n == m,
+                MankaiObject::Number(m) => n == m || (n.is_nan() && m.is_nan()),
                 _ => false,
             },
             MankaiObject::String(s) => match other {
@@ -74,9 +213,109 @@ impl PartialEq for MankaiObject {
                 MankaiObject::Bool(b2) => b1 == b2,
                 _ => false,
             },
-            MankaiObject::SpecialForm(_) => false,
-            MankaiObject::Native(_) => false,
-            MankaiObject::Function { .. } => false,
+            MankaiObject::Keyword(k1) => match other {
+                MankaiObject::Keyword(k2) => k1 == k2,
+                _ => false,
+            },
+            MankaiObject::Char(c1) => match other {
+                MankaiObject::Char(c2) => c1 == c2,
+                _ => false,
+            },
+            MankaiObject::Map(m1) => match other {
+                MankaiObject::Map(m2) => m1 == m2,
+                _ => false,
+            },
+            MankaiObject::Set(s1) => match other {
+                MankaiObject::Set(s2) => s1 == s2,
+                _ => false,
+            },
+            MankaiObject::Vector(v1) => match other {
+                MankaiObject::Vector(v2) => v1 == v2,
+                _ => false,
+            },
+            MankaiObject::Record(r1) => match other {
+                MankaiObject::Record(r2) => r1 == r2,
+                _ => false,
+            },
+            MankaiObject::Promise(_)
+            | MankaiObject::LazySeq(_)
+            | MankaiObject::Generator(_)
+            | MankaiObject::Continuation(_)
+            | MankaiObject::Parameter(_)
+            | MankaiObject::Box(_)
+            | MankaiObject::Atom(_)
+            | MankaiObject::SpecialForm(_)
+            | MankaiObject::Native(_)
+            | MankaiObject::Function { .. }
+            | MankaiObject::RecordFunction(_) => self.is_identical(other),
+        }
+    }
+}
+
+impl Eq for MankaiObject {}
+
+impl PartialOrd for MankaiObject {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MankaiObject {
+    /// Total order used by `sort`, consistent with equality. Values of
+    /// different kinds are ordered by kind: bools, numbers, characters,
+    /// strings, keywords, lists, vectors, maps, sets, records, promises, lazy
+    /// sequences, generators, continuations, parameters, boxes, atoms, special
+    /// forms, native functions, record functions and user-defined functions.
+    /// Values of the same kind are ordered this way:
+    /// - `false` comes before `true`;
+    /// - numbers by value, with NaN after every other number;
+    /// - characters by code point, strings and keywords lexicographically;
+    /// - lists and vectors lexicographically;
+    /// - maps as the list of their entries sorted by key, sets as the list of
+    ///   their sorted elements;
+    /// - records by type name, then field by field;
+    /// - promises, lazy sequences, generators, continuations, parameters,
+    ///   boxes, atoms, functions and special forms arbitrarily, but
+    ///   consistently during a run.
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (MankaiObject::Bool(b1), MankaiObject::Bool(b2)) => b1.cmp(b2),
+            (MankaiObject::Number(n), MankaiObject::Number(m)) => match n.partial_cmp(m) {
+                Some(ordering) => ordering,
+                None => n.is_nan().cmp(&m.is_nan()),
+            },
+            (MankaiObject::Char(c1), MankaiObject::Char(c2)) => c1.cmp(c2),
+            (MankaiObject::String(s), MankaiObject::String(t)) => s.cmp(t),
+            (MankaiObject::Keyword(k1), MankaiObject::Keyword(k2)) => k1.cmp(k2),
+            (MankaiObject::List(l1), MankaiObject::List(l2)) => l1.iter().cmp(l2.iter()),
+            (MankaiObject::Vector(v1), MankaiObject::Vector(v2)) => v1.cmp(v2),
+            (MankaiObject::Map(m1), MankaiObject::Map(m2)) => {
+                let mut e1: Vec<_> = m1.iter().collect();
+                let mut e2: Vec<_> = m2.iter().collect();
+                e1.sort_by_key(|(key, _)| *key);
+                e2.sort_by_key(|(key, _)| *key);
+                e1.cmp(&e2)
+            }
+            (MankaiObject::Set(s1), MankaiObject::Set(s2)) => {
+                let mut e1: Vec<_> = s1.iter().collect();
+                let mut e2: Vec<_> = s2.iter().collect();
+                e1.sort();
+                e2.sort();
+                e1.cmp(&e2)
+            }
+            (MankaiObject::Record(r1), MankaiObject::Record(r2)) => r1
+                .record_type
+                .name
+                .cmp(&r2.record_type.name)
+                .then_with(|| {
+                    let t1 = Rc::as_ptr(&r1.record_type) as usize;
+                    t1.cmp(&(Rc::as_ptr(&r2.record_type) as usize))
+                })
+                .then_with(|| r1.values.cmp(&r2.values)),
+            _ => self
+                .rank()
+                .cmp(&other.rank())
+                .then_with(|| self.address().cmp(&other.address())),
         }
     }
 }
@@ -85,7 +324,7 @@ impl ToString for MankaiObject {
     fn to_string(&self) -> String {
         match self {
             MankaiObject::Number(n) => n.to_string(),
-            MankaiObject::String(s) => format!("\"{}\"", s),
+            MankaiObject::String(s) => escape_string(s),
             MankaiObject::List(list) => {
                 let mut s = String::from("(");
                 for (i, elem) in list.iter().enumerate() {
@@ -100,55 +339,281 @@ impl ToString for MankaiObject {
             }
             MankaiObject::Bool(true) => String::from("true"),
             MankaiObject::Bool(false) => String::from("false"),
+            MankaiObject::Keyword(k) => format!(":{}", k),
+            MankaiObject::Char(c) => escape_char(*c),
+            MankaiObject::Map(map) => {
+                let mut s = String::from("{");
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i != 0 {
+                        s.push(' ');
+                    }
+                    s.push_str(&key.to_string());
+                    s.push(' ');
+                    s.push_str(&value.to_string());
+                }
+                s.push('}');
+
+                s
+            }
+            MankaiObject::Set(set) => {
+                let mut s = String::from("#{");
+                for (i, elem) in set.iter().enumerate() {
+                    if i != 0 {
+                        s.push(' ');
+                    }
+                    s.push_str(&elem.to_string());
+                }
+                s.push('}');
+
+                s
+            }
+            MankaiObject::Vector(vector) => {
+                let mut s = String::from("[");
+                for (i, elem) in vector.iter().enumerate() {
+                    if i != 0 {
+                        s.push(' ');
+                    }
+                    s.push_str(&elem.to_string());
+                }
+                s.push(']');
+
+                s
+            }
+            MankaiObject::Record(record) => record.to_string(),
+            MankaiObject::Promise(promise) => promise.to_string(),
+            MankaiObject::LazySeq(sequence) => sequence.to_string(),
+            MankaiObject::Generator(generator) => generator.to_string(),
+            MankaiObject::Continuation(_) => String::from("<continuation>"),
+            MankaiObject::Parameter(_) => String::from("<parameter>"),
+            MankaiObject::Box(reference) => reference.to_string("box"),
+            MankaiObject::Atom(reference) => reference.to_string("atom"),
+            MankaiObject::SpecialForm(SpecialForm::Yield | SpecialForm::CallCc) => {
+                String::from("<native function>")
+            }
             MankaiObject::SpecialForm(_) => String::from("<special form>"),
             MankaiObject::Native(_) => String::from("<native function>"),
             MankaiObject::Function { .. } => String::from("<user-defined function>"),
+            MankaiObject::RecordFunction(_) => String::from("<record function>"),
+        }
+    }
+}
+
+impl Hash for MankaiObject {
+    /// Hash a value consistently with equality: maps and sets hash the same no
+    /// matter the insertion order, numbers hash the same if they're equal
+    /// (like `1` and `1.0`) and functions and special forms hash their
+    /// identity.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            MankaiObject::Number(n) => n.hash(state),
+            MankaiObject::String(s) => s.hash(state),
+            MankaiObject::List(list) => {
+                list.len().hash(state);
+                list.iter().for_each(|elem| elem.hash(state));
+            }
+            MankaiObject::Bool(b) => b.hash(state),
+            MankaiObject::Keyword(k) => k.hash(state),
+            MankaiObject::Char(c) => c.hash(state),
+            MankaiObject::Map(map) => {
+                map.len().hash(state);
+                unordered_hash(map.iter()).hash(state);
+            }
+            MankaiObject::Set(set) => {
+                set.len().hash(state);
+                unordered_hash(set.iter()).hash(state);
+            }
+            MankaiObject::Vector(vector) => vector.hash(state),
+            MankaiObject::Record(record) => {
+                record.record_type.name.hash(state);
+                record.values.hash(state);
+            }
+            MankaiObject::Promise(_)
+            | MankaiObject::LazySeq(_)
+            | MankaiObject::Generator(_)
+            | MankaiObject::Continuation(_)
+            | MankaiObject::Parameter(_)
+            | MankaiObject::Box(_)
+            | MankaiObject::Atom(_)
+            | MankaiObject::SpecialForm(_)
+            | MankaiObject::Native(_)
+            | MankaiObject::Function { .. }
+            | MankaiObject::RecordFunction(_) => self.address().hash(state),
         }
     }
 }
 
+/// Combine the hashes of some items so that the result doesn't depend on
+/// their order.
+fn unordered_hash<T: Hash>(items: impl Iterator<Item = T>) -> u64 {
+    items.fold(0, |sum: u64, item| {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        sum.wrapping_add(hasher.finish())
+    })
+}
+
+/// Quote a string escaping its special characters, so that the result can be
+/// read back by the lexer.
+fn escape_string(string: &str) -> String {
+    let mut escaped = String::from("\"");
+
+    for c in string.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+/// Write a character as a character literal.
+fn escape_char(c: char) -> String {
+    match c {
+        ' ' => String::from("#\\space"),
+        '\n' => String::from("#\\newline"),
+        '\t' => String::from("#\\tab"),
+        c if c.is_control() || c.is_whitespace() => format!("#\\u{{{:x}}}", c as u32),
+        c => format!("#\\{}", c),
+    }
+}
+
 impl MankaiObject {
+    /// Identity, as checked by `eq?`: booleans, numbers (of the same
+    /// exactness), characters and keywords are identical if they're equal,
+    /// empty lists are all identical, and other values only if they're the
+    /// same object (copies of a value are the same object, but equal values
+    /// built separately are not).
+    pub fn is_identical(&self, other: &MankaiObject) -> bool {
+        match (self, other) {
+            (MankaiObject::Number(n), MankaiObject::Number(m)) => {
+                n.is_exact() == m.is_exact() && self == other
+            }
+            (MankaiObject::Bool(_), MankaiObject::Bool(_))
+            | (MankaiObject::Char(_), MankaiObject::Char(_))
+            | (MankaiObject::Keyword(_), MankaiObject::Keyword(_)) => self == other,
+            (MankaiObject::String(s), MankaiObject::String(t)) => Rc::ptr_eq(s, t),
+            (MankaiObject::List(l1), MankaiObject::List(l2)) => l1.ptr_eq(l2),
+            (MankaiObject::Vector(v1), MankaiObject::Vector(v2)) => Rc::ptr_eq(v1, v2),
+            (MankaiObject::Map(m1), MankaiObject::Map(m2)) => m1.ptr_eq(m2),
+            (MankaiObject::Set(s1), MankaiObject::Set(s2)) => s1.ptr_eq(s2),
+            (MankaiObject::Promise(p1), MankaiObject::Promise(p2)) => p1.ptr_eq(p2),
+            (MankaiObject::LazySeq(l1), MankaiObject::LazySeq(l2)) => l1.ptr_eq(l2),
+            (MankaiObject::Generator(g1), MankaiObject::Generator(g2)) => g1.ptr_eq(g2),
+            (MankaiObject::Continuation(k1), MankaiObject::Continuation(k2)) => k1.ptr_eq(k2),
+            (MankaiObject::Parameter(p1), MankaiObject::Parameter(p2)) => p1.ptr_eq(p2),
+            (MankaiObject::Box(b1), MankaiObject::Box(b2)) => b1.ptr_eq(b2),
+            (MankaiObject::Atom(a1), MankaiObject::Atom(a2)) => a1.ptr_eq(a2),
+            (MankaiObject::Record(r1), MankaiObject::Record(r2)) => {
+                r1.is_a(&r2.record_type) && Rc::ptr_eq(&r1.values, &r2.values)
+            }
+            (MankaiObject::SpecialForm(_), MankaiObject::SpecialForm(_))
+            | (MankaiObject::Native(_), MankaiObject::Native(_))
+            | (MankaiObject::Function { .. }, MankaiObject::Function { .. })
+            | (MankaiObject::RecordFunction(_), MankaiObject::RecordFunction(_)) => {
+                self.address() == other.address()
+            }
+            _ => false,
+        }
+    }
+
+    /// Position of the kind of the object in the order of kinds.
+    fn rank(&self) -> u8 {
+        match self {
+            MankaiObject::Bool(_) => 0,
+            MankaiObject::Number(_) => 1,
+            MankaiObject::Char(_) => 2,
+            MankaiObject::String(_) => 3,
+            MankaiObject::Keyword(_) => 4,
+            MankaiObject::List(_) => 5,
+            MankaiObject::Vector(_) => 6,
+            MankaiObject::Map(_) => 7,
+            MankaiObject::Set(_) => 8,
+            MankaiObject::Record(_) => 9,
+            MankaiObject::Promise(_) => 10,
+            MankaiObject::LazySeq(_) => 11,
+            MankaiObject::Generator(_) => 12,
+            MankaiObject::Continuation(_) => 13,
+            MankaiObject::Parameter(_) => 14,
+            MankaiObject::Box(_) => 15,
+            MankaiObject::Atom(_) => 16,
+            MankaiObject::SpecialForm(SpecialForm::Yield | SpecialForm::CallCc) => 18,
+            MankaiObject::SpecialForm(_) => 17,
+            MankaiObject::Native(_) => 18,
+            MankaiObject::RecordFunction(_) => 19,
+            MankaiObject::Function { .. } => 20,
+        }
+    }
+
+    /// Address identifying a function, special form, promise, lazy sequence,
+    /// generator, continuation, parameter, box or atom, used to compare and
+    /// hash them.
+    /// Record functions are identified by the address of their type and the
+    /// position of their kind. Zero for other objects.
+    fn address(&self) -> (usize, usize) {
+        match self {
+            MankaiObject::SpecialForm(form) => (form.address(), 0),
+            MankaiObject::Native(function) => (*function as usize, 0),
+            MankaiObject::Function { clauses, .. } => (Rc::as_ptr(clauses) as usize, 0),
+            MankaiObject::Promise(promise) => (promise.address(), 0),
+            MankaiObject::LazySeq(sequence) => (sequence.address(), 0),
+            MankaiObject::Generator(generator) => (generator.address(), 0),
+            MankaiObject::Continuation(continuation) => (continuation.address(), 0),
+            MankaiObject::Parameter(parameter) => (parameter.address(), 0),
+            MankaiObject::Box(reference) | MankaiObject::Atom(reference) => {
+                (reference.address(), 0)
+            }
+            MankaiObject::RecordFunction(function) => (
+                Rc::as_ptr(&function.record_type) as usize,
+                function.kind.index(),
+            ),
+            _ => (0, 0),
+        }
+    }
+
     /// Call the object with arguments.
     /// It the object is a function call it, if it's something else report a
     /// runtime error.
-    fn call(
+    pub fn call(
         &self,
         interpreter: &mut Interpreter,
         arguments: Vec<MankaiObject>,
     ) -> Result<MankaiObject, RuntimeError> {
         match self {
-            MankaiObject::Native(function) => function(arguments),
+            MankaiObject::Native(function) => function(interpreter, arguments),
+            MankaiObject::SpecialForm(SpecialForm::Yield) => {
+                native_functions::yield_value(interpreter, arguments)
+            }
+            MankaiObject::SpecialForm(SpecialForm::CallCc) => {
+                native_functions::call_cc(interpreter, arguments)
+            }
+            MankaiObject::RecordFunction(function) => function.call(arguments),
+            MankaiObject::Continuation(continuation) => continuation.call(interpreter, arguments),
+            MankaiObject::Parameter(parameter) => {
+                if !arguments.is_empty() {
+                    return Err(RuntimeError::new(
+                        "parameters must be called without arguments!",
+                    ));
+                }
+
+                Ok(parameter.get())
+            }
             MankaiObject::Function {
                 name,
-                arguments_identifiers,
-                body,
+                clauses,
+                captured,
             } => {
-                // Arity check.
-                if arguments_identifiers.len() != arguments.len() {
-                    let function_name = match name {
-                        Some(string) => string,
-                        None => "anonymous function",
-                    };
-
-                    return Err(RuntimeError::new(&format!(
-                        "found {} arguments but '{}' requires {}!",
-                        arguments.len(),
-                        function_name,
-                        arguments_identifiers.len()
-                    )));
-                }
-
-                // Extend the environment.
-                interpreter.environment.extend();
+                let clause = enter_clause(interpreter, name, clauses, captured, arguments)?;
 
-                for (identifier, value) in arguments_identifiers.iter().zip(arguments.iter()) {
-                    interpreter.environment.define(identifier, value.clone());
-                }
-
-                // Evaluate the body of the function.
-                let result = interpreter.evaluate(body);
-
-                // Restrict the environment and return.
+                // Evaluate the body of the clause, restrict the environment and
+                // return.
+                let result = interpreter.evaluate(&clause.body);
                 interpreter.environment.restrict();
                 result
             }
@@ -160,6 +625,55 @@ impl MankaiObject {
     }
 }
 
+/// Select the first clause of the function `name` accepting the arguments,
+/// extend the environment with the bindings captured by the function and bind
+/// the arguments to its parameters. The caller evaluates the body and
+/// restricts the environment.
+pub fn enter_clause<'a>(
+    interpreter: &mut Interpreter,
+    name: &Option<Rc<str>>,
+    clauses: &'a [Clause],
+    captured: &HashMap<String, MankaiObject>,
+    arguments: Vec<MankaiObject>,
+) -> Result<&'a Clause, RuntimeError> {
+    let function_name = match name {
+        Some(string) => string,
+        None => "anonymous function",
+    };
+
+    // Select the first clause accepting this number of arguments.
+    let clause = match clauses
+        .iter()
+        .find(|clause| clause.parameters.accepts(arguments.len()))
+    {
+        Some(clause) => clause,
+        None => {
+            let parameter_lists: Vec<&Parameters> =
+                clauses.iter().map(|clause| &clause.parameters).collect();
+
+            return Err(RuntimeError::new(&format!(
+                "found {} arguments but '{}' expected {}!",
+                arguments.len(),
+                function_name,
+                describe_arities(&parameter_lists)
+            )));
+        }
+    };
+
+    // Extend the environment and bind the arguments, which hide the captured
+    // bindings with the same names.
+    interpreter.environment.extend_with(captured);
+    if let Err(err) = clause
+        .parameters
+        .bind(interpreter, function_name, arguments)
+    {
+        interpreter.environment.restrict();
+        return Err(err);
+    }
+
+    Ok(clause)
+}
+
 /// A Mankai interepreter.
 pub struct Interpreter {
     /// The environment.
@@ -170,6 +684,11 @@ pub struct Interpreter {
     native_functions: Vec<String>,
     /// Vector of reserved names for constants.
     constants: Vec<String>,
+    /// Identifiers of the runs of the evaluator going on, from the outermost
+    /// one to the innermost one.
+    pub(crate) runs: Vec<usize>,
+    /// Number of runs of the evaluator started so far.
+    pub(crate) run_count: usize,
 }
 
 impl Default for Interpreter {
@@ -178,9 +697,18 @@ impl Default for Interpreter {
             environment: Environment::new(),
             special_forms: vec![
                 String::from("define!"),
+                String::from("case-lambda!"),
+                String::from("defrecord!"),
+                String::from("deftype!"),
                 String::from("defun!"),
+                String::from("delay!"),
                 String::from("if!"),
                 String::from("lambda!"),
+                String::from("lazy-cons!"),
+                String::from("let/ec!"),
+                String::from("parameterize!"),
+                String::from("let!"),
+                String::from("match!"),
                 String::from("set!"),
             ],
             native_functions: vec![
@@ -195,8 +723,11 @@ impl Default for Interpreter {
                 String::from("car"),
                 String::from("cdr"),
                 String::from("cons"),
+                String::from("eq?"),
+                String::from("equal?"),
                 String::from("bool?"),
                 String::from("list?"),
+                String::from("keyword?"),
                 String::from("number?"),
                 String::from("string?"),
                 String::from("list"),
@@ -204,8 +735,144 @@ impl Default for Interpreter {
                 String::from("or"),
                 String::from("string-concat"),
                 String::from("to-string"),
+                String::from("write-to-string"),
+                String::from("read-string"),
+                String::from("any?"),
+                String::from("apply"),
+                String::from("every?"),
+                String::from("filter"),
+                String::from("find"),
+                String::from("fold-left"),
+                String::from("fold-right"),
+                String::from("map"),
+                String::from("reduce"),
+                String::from("sort-by"),
+                String::from("append"),
+                String::from("drop"),
+                String::from("flatten"),
+                String::from("index-of"),
+                String::from("last"),
+                String::from("length"),
+                String::from("member?"),
+                String::from("nth"),
+                String::from("range"),
+                String::from("reverse"),
+                String::from("sort"),
+                String::from("take"),
+                String::from("unique"),
+                String::from("zip"),
+                String::from("list->string"),
+                String::from("string->list"),
+                String::from("string->number"),
+                String::from("string-contains?"),
+                String::from("string-downcase"),
+                String::from("string-ends-with?"),
+                String::from("string-index"),
+                String::from("string-join"),
+                String::from("string-length"),
+                String::from("string-replace"),
+                String::from("string-reverse"),
+                String::from("string-split"),
+                String::from("string-starts-with?"),
+                String::from("string-trim"),
+                String::from("string-upcase"),
+                String::from("substring"),
+                String::from("abs"),
+                String::from("acos"),
+                String::from("asin"),
+                String::from("atan"),
+                String::from("atan2"),
+                String::from("ceil"),
+                String::from("cos"),
+                String::from("exp"),
+                String::from("floor"),
+                String::from("log"),
+                String::from("max"),
+                String::from("min"),
+                String::from("mod"),
+                String::from("pow"),
+                String::from("quotient"),
+                String::from("remainder"),
+                String::from("round"),
+                String::from("sin"),
+                String::from("sqrt"),
+                String::from("tan"),
+                String::from("truncate"),
+                String::from("vector"),
+                String::from("vector?"),
+                String::from("vector-ref"),
+                String::from("vector-set"),
+                String::from("vector-length"),
+                String::from("vector->list"),
+                String::from("list->vector"),
+                String::from("hash-map"),
+                String::from("map?"),
+                String::from("get"),
+                String::from("assoc"),
+                String::from("dissoc"),
+                String::from("contains-key?"),
+                String::from("keys"),
+                String::from("values"),
+                String::from("map-merge"),
+                String::from("set"),
+                String::from("set?"),
+                String::from("set-add"),
+                String::from("set-remove"),
+                String::from("set-member?"),
+                String::from("union"),
+                String::from("intersection"),
+                String::from("difference"),
+                String::from("subset?"),
+                String::from("set->list"),
+                String::from("force"),
+                String::from("make-promise"),
+                String::from("promise?"),
+                String::from("iterate"),
+                String::from("lazy-map"),
+                String::from("lazy-filter"),
+                String::from("lazy-seq?"),
+                String::from("make-generator"),
+                String::from("next"),
+                String::from("generator-done?"),
+                String::from("generator?"),
+                String::from("yield"),
+                String::from("call/cc"),
+                String::from("make-parameter"),
+                String::from("parameter?"),
+                String::from("box"),
+                String::from("unbox"),
+                String::from("set-box!"),
+                String::from("box?"),
+                String::from("atom"),
+                String::from("deref"),
+                String::from("swap!"),
+                String::from("reset!"),
+                String::from("atom?"),
+                String::from("char?"),
+                String::from("char-alphabetic?"),
+                String::from("char-numeric?"),
+                String::from("char-whitespace?"),
+                String::from("char-upper-case?"),
+                String::from("char-lower-case?"),
+                String::from("char-upcase"),
+                String::from("char-downcase"),
+                String::from("char->integer"),
+                String::from("integer->char"),
+                String::from("char->string"),
+                String::from("exact"),
+                String::from("inexact"),
+                String::from("exact?"),
+                String::from("inexact?"),
+                String::from("integer?"),
             ],
-            constants: vec![String::from("true"), String::from("false")],
+            constants: vec![
+                String::from("true"),
+                String::from("false"),
+                String::from("pi"),
+                String::from("e"),
+            ],
+            runs: Vec::new(),
+            run_count: 0,
         }
     }
 }
@@ -234,52 +901,40 @@ impl Interpreter {
     }
 
     /// Evaluate an atom.
-    fn evaluate_atom(&self, atom: &Token) -> Result<MankaiObject, RuntimeError> {
+    pub(crate) fn evaluate_atom(&self, atom: &Token) -> Result<MankaiObject, RuntimeError> {
         match &atom.kind {
-            TokenKind::Number(n) => Ok(MankaiObject::Number(*n)),
-            TokenKind::String(s) => Ok(MankaiObject::String(s.to_string())),
+            TokenKind::Number(n) => Ok(MankaiObject::Number(n.clone())),
+            TokenKind::String(s) => Ok(MankaiObject::String(s.as_str().into())),
+            TokenKind::Keyword(k) => Ok(MankaiObject::Keyword(k.to_string())),
+            TokenKind::Char(c) => Ok(MankaiObject::Char(*c)),
             TokenKind::Identifier => self.environment.get(atom),
             _ => Err(RuntimeError::new("failed to convert atom to value")),
         }
     }
-
-    /// Evaluate a list: can result in evaluating a special form or a function
-    /// (user-defined or native).
-    fn evaluate_list(&mut self, list: &[Sexp]) -> Result<MankaiObject, RuntimeError> {
-        let callee = self.evaluate(list.get(0).unwrap())?;
-        let arguments: Vec<&Sexp> = list.iter().skip(1).collect();
-
-        match callee {
-            MankaiObject::SpecialForm(special_form) => special_form(self, arguments),
-            _ => {
-                // Evaluate the arguments.
-                let mut evaluated_arguments = Vec::new();
-                for expr in arguments {
-                    let value = self.evaluate(expr)?;
-                    evaluated_arguments.push(value);
-                }
-
-                // Call the function.
-                callee.call(self, evaluated_arguments)
-            }
-        }
-    }
-
-    /// Evaluate an expression.
-    pub fn evaluate(&mut self, expr: &Sexp) -> Result<MankaiObject, RuntimeError> {
-        match expr {
-            Sexp::Atom(token) => self.evaluate_atom(token),
-            Sexp::List(list) => self.evaluate_list(list),
-        }
-    }
 }
 
 #[cfg(test)]
 mod interpreter_test {
-    use super::{Interpreter, MankaiObject};
+    use super::{Clause, Interpreter, MankaiObject};
     use crate::lexer::Lexer;
+    use crate::number::Number;
+    use crate::parameters::Parameters;
     use crate::parser::{Parser, Sexp};
     use crate::token::*;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    /// Lex, parse and evaluate a single expression.
+    fn run(source: &str, interpreter: &mut Interpreter) -> Result<MankaiObject, String> {
+        let mut lexer = Lexer::new(String::from(source));
+        if let Err(err) = lexer.scan() {
+            return Err(err.message);
+        }
+
+        let mut parser = Parser::new(lexer.tokens);
+        let expr = parser.parse().map_err(|err| err.message)?;
+        interpreter.evaluate(&expr).map_err(|err| err.message)
+    }
 
     #[test]
     fn atom_evaluation() {
@@ -294,7 +949,7 @@ mod interpreter_test {
 
         match parser.parse() {
             Ok(expr) => match interpreter.evaluate(&expr) {
-                Ok(value) => assert_eq!(value, MankaiObject::Number(5.0)),
+                Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(5))),
                 Err(err) => panic!(err.message),
             },
             Err(err) => panic!(err.message),
@@ -311,7 +966,7 @@ mod interpreter_test {
 
         match parser.parse() {
             Ok(expr) => match interpreter.evaluate(&expr) {
-                Ok(value) => assert_eq!(value, MankaiObject::String(String::from("foo"))),
+                Ok(value) => assert_eq!(value, MankaiObject::String("foo".into())),
                 Err(err) => panic!(err.message),
             },
             Err(err) => panic!(err.message),
@@ -346,12 +1001,12 @@ mod interpreter_test {
 
         interpreter.environment.define(
             &Token::new(String::from("bar"), TokenKind::Identifier),
-            MankaiObject::Number(2.0),
+            MankaiObject::Number(Number::from(2)),
         );
 
         match parser.parse() {
             Ok(expr) => match interpreter.evaluate(&expr) {
-                Ok(value) => assert_eq!(value, MankaiObject::Number(2.0)),
+                Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(2))),
                 Err(err) => panic!(err.message),
             },
             Err(err) => panic!(err.message),
@@ -370,7 +1025,7 @@ mod interpreter_test {
 
         match parser.parse() {
             Ok(expr) => match interpreter.evaluate(&expr) {
-                Ok(value) => assert_eq!(value, MankaiObject::String(String::from("bar"))),
+                Ok(value) => assert_eq!(value, MankaiObject::String("bar".into())),
                 Err(err) => panic!(err.message),
             },
             Err(err) => panic!(err.message),
@@ -385,7 +1040,7 @@ mod interpreter_test {
 
         match parser.parse() {
             Ok(expr) => match interpreter.evaluate(&expr) {
-                Ok(value) => assert_eq!(value, MankaiObject::String(String::from("bar"))),
+                Ok(value) => assert_eq!(value, MankaiObject::String("bar".into())),
                 Err(err) => panic!(err.message),
             },
             Err(err) => panic!(err.message),
@@ -407,25 +1062,1418 @@ mod interpreter_test {
         interpreter.environment.define(
             &Token::new(String::from("my-addition"), TokenKind::Identifier),
             MankaiObject::Function {
-                name: Some(String::from("my-addition")),
-                arguments_identifiers: vec![
-                    Token::new(String::from("first"), TokenKind::Identifier),
-                    Token::new(String::from("second"), TokenKind::Identifier),
-                ],
-                body: Sexp::List(vec![
-                    Sexp::Atom(Token::new(String::from("+"), TokenKind::Identifier)),
-                    Sexp::Atom(Token::new(String::from("first"), TokenKind::Identifier)),
-                    Sexp::Atom(Token::new(String::from("second"), TokenKind::Identifier)),
-                ]),
+                name: Some("my-addition".into()),
+                clauses: Rc::new(vec![Clause {
+                    parameters: Parameters::new(vec![
+                        Token::new(String::from("first"), TokenKind::Identifier),
+                        Token::new(String::from("second"), TokenKind::Identifier),
+                    ]),
+                    body: Sexp::List(Rc::new(vec![
+                        Sexp::Atom(Token::new(String::from("+"), TokenKind::Identifier)),
+                        Sexp::Atom(Token::new(String::from("first"), TokenKind::Identifier)),
+                        Sexp::Atom(Token::new(String::from("second"), TokenKind::Identifier)),
+                    ])),
+                }]),
+                captured: Rc::new(HashMap::new()),
             },
         );
 
         match parser.parse() {
             Ok(expr) => match interpreter.evaluate(&expr) {
-                Ok(value) => assert_eq!(value, MankaiObject::Number(3.0)),
+                Ok(value) => assert_eq!(value, MankaiObject::Number(Number::from(3))),
                 Err(err) => panic!(err.message),
             },
             Err(err) => panic!(err.message),
         }
     }
+
+    #[test]
+    fn optional_rest_and_key_parameters() {
+        let mut interpreter = Interpreter::new();
+
+        // Optional parameters, with and without default values.
+        run(
+            "(defun! f (a &optional (b (+ a 1)) c) (list a b c))",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(f 1)", &mut interpreter).unwrap().to_string(),
+            "(1 2 ())"
+        );
+        assert_eq!(
+            run("(f 1 5 6)", &mut interpreter).unwrap().to_string(),
+            "(1 5 6)"
+        );
+        assert_eq!(
+            run("(f)", &mut interpreter).unwrap_err(),
+            "found 0 arguments but 'f' expected 1 to 3 arguments!"
+        );
+        assert_eq!(
+            run("(f 1 2 3 4)", &mut interpreter).unwrap_err(),
+            "found 4 arguments but 'f' expected 1 to 3 arguments!"
+        );
+
+        // Rest parameter.
+        run(
+            "(defun! g (a &rest others) (cons others a))",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(g 1 2 3)", &mut interpreter).unwrap().to_string(),
+            "(2 3 1)"
+        );
+        assert_eq!(
+            run("(g)", &mut interpreter).unwrap_err(),
+            "found 0 arguments but 'g' expected at least 1 argument!"
+        );
+        run(
+            "(defun! eleven (a b c d e f g h i j k) a)",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(eleven)", &mut interpreter).unwrap_err(),
+            "found 0 arguments but 'eleven' expected 11 arguments!"
+        );
+        run(
+            "(defun! up-to-21 (&optional a b c d e f g h i j k l m n o p q r s t u) a)",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(apply up-to-21 (range 0 22))", &mut interpreter).unwrap_err(),
+            "found 22 arguments but 'up-to-21' expected 0 to 21 arguments!"
+        );
+
+        // Keyword parameters.
+        run(
+            "(define! h (lambda! (a &key (b 2) c) (list a b c)))",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(h 1 :c 3)", &mut interpreter).unwrap().to_string(),
+            "(1 2 3)"
+        );
+        assert_eq!(
+            run("(h 1 :d 3)", &mut interpreter).unwrap_err(),
+            "unknown keyword argument ':d' to 'anonymous function'!"
+        );
+        assert_eq!(
+            run("(h 1 :b)", &mut interpreter).unwrap_err(),
+            "odd number of keyword arguments to 'anonymous function'!"
+        );
+
+        // Malformed parameter lists.
+        assert!(run("(lambda! (&rest) 1)", &mut interpreter).is_err());
+        assert!(run("(lambda! (&key a &optional b) 1)", &mut interpreter).is_err());
+        assert!(run("(lambda! (a a) 1)", &mut interpreter).is_err());
+    }
+
+    #[test]
+    fn multi_clause_functions() {
+        let mut interpreter = Interpreter::new();
+
+        // Multi-clause `defun!`.
+        run(
+            "(defun! my-range ((end) (my-range 0 end 1)) ((start end) (my-range start end 1)) ((start end step) (if! (< start end) (cons (my-range (+ start step) end step) start) (list))))",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(my-range 3)", &mut interpreter).unwrap().to_string(),
+            "(2 1 0)"
+        );
+        assert_eq!(
+            run("(my-range 1 7 2)", &mut interpreter)
+                .unwrap()
+                .to_string(),
+            "(5 3 1)"
+        );
+        assert_eq!(
+            run("(my-range)", &mut interpreter).unwrap_err(),
+            "found 0 arguments but 'my-range' expected 1, 2 or 3 arguments!"
+        );
+
+        // `case-lambda!`, clauses are tried in order.
+        run(
+            "(define! f (case-lambda! (() \"none\") ((x) \"one\") ((x &rest xs) \"many\")))",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(f)", &mut interpreter).unwrap(),
+            MankaiObject::String("none".into())
+        );
+        assert_eq!(
+            run("(f 1)", &mut interpreter).unwrap(),
+            MankaiObject::String("one".into())
+        );
+        assert_eq!(
+            run("(f 1 2 3)", &mut interpreter).unwrap(),
+            MankaiObject::String("many".into())
+        );
+
+        run(
+            "(define! g (case-lambda! ((x) x) ((x y z &rest others) x)))",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(g 1 2)", &mut interpreter).unwrap_err(),
+            "found 2 arguments but 'anonymous function' expected 1 or at least 3 arguments!"
+        );
+    }
+
+    #[test]
+    fn higher_order_natives() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("(map (lambda! (x) (* x x)) (list 1 2 3))", "(1 4 9)");
+        check("(map + (list 1 2 3) (list 10 20))", "(11 22)");
+        check("(filter (lambda! (x) (> x 1)) (list 1 2 3))", "(2 3)");
+        check("(reduce + (list 1 2 3))", "6");
+        check("(reduce + 10 (list 1 2 3))", "16");
+        check("(fold-left list 0 (list 1 2))", "((0 1) 2)");
+        check("(fold-right list 0 (list 1 2))", "(1 (2 0))");
+        check("(apply + 1 2 (list 3 4))", "10");
+        check("(any? (lambda! (x) (> x 2)) (list 1 2 3))", "true");
+        check("(every? (lambda! (x) (> x 2)) (list 1 2 3))", "false");
+        check("(find (lambda! (x) (> x 1)) (list 1 2 3))", "2");
+        check("(find (lambda! (x) (> x 5)) (list 1 2 3) false)", "false");
+        check(
+            "(sort-by (lambda! (x) (car x)) (list (list 3 \"c\") (list 1 \"a\") (list 2 \"b\")))",
+            "((1 \"a\") (2 \"b\") (3 \"c\"))",
+        );
+
+        // Errors inside the callbacks are propagated.
+        assert!(run("(map car (list (list) 1))", &mut interpreter).is_err());
+        assert_eq!(
+            run("(filter (lambda! (x) x) (list 1))", &mut interpreter).unwrap_err(),
+            "predicate given to 'filter' must return a boolean!"
+        );
+    }
+
+    #[test]
+    fn list_natives() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("(length (list 1 2 3))", "3");
+        check("(nth (list 1 2 3) 1)", "2");
+        check("(last (list 1 2 3))", "3");
+        check("(append (list 1) (list) (list 2 3))", "(1 2 3)");
+        check("(reverse (list 1 2 3))", "(3 2 1)");
+        check("(range 3)", "(0 1 2)");
+        check("(range 1 2 0.25)", "(1 1.25 1.5 1.75)");
+        check("(range 3 0 (- 1))", "(3 2 1)");
+        check("(length (range (pow 10 400) (+ (pow 10 400) 2)))", "2");
+        check("(take (list 1 2 3) 2)", "(1 2)");
+        check("(drop (list 1 2 3) 5)", "()");
+        check("(zip (list 1 2 3) (list 4 5))", "((1 4) (2 5))");
+        check("(flatten (list 1 (list 2 (list 3)) (list)))", "(1 2 3)");
+        check("(member? (list 1 2 3) 2)", "true");
+        check("(index-of (list 1 2 3) 4)", "-1");
+        check("(sort (list 3 1 2))", "(1 2 3)");
+        check("(sort (list \"b\" \"a\"))", "(\"a\" \"b\")");
+        check("(unique (list 1 2 1 3 2))", "(1 2 3)");
+
+        assert_eq!(
+            run("(nth (list 1 2 3) 3)", &mut interpreter).unwrap_err(),
+            "index 3 is out of bounds for a list of length 3!"
+        );
+        assert_eq!(
+            run("(range inf)", &mut interpreter).unwrap_err(),
+            "the bounds of 'range' must be finite numbers!"
+        );
+        assert_eq!(
+            run("(range 0 1 1e-300)", &mut interpreter).unwrap_err(),
+            "'range' can't make a list of more than 1000000 elements!"
+        );
+        assert_eq!(
+            run("(nth (list 1 2 3) 1.5)", &mut interpreter).unwrap_err(),
+            "2-th argument to 'nth' must be a non-negative integer!"
+        );
+        assert_eq!(
+            run("(length 1)", &mut interpreter).unwrap_err(),
+            "1-th argument to 'length' must be a list or a vector!"
+        );
+    }
+
+    #[test]
+    fn string_natives() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("(string-length \"héllo\")", "5");
+        check("(substring \"héllo\" 1 3)", "\"él\"");
+        check("(substring \"héllo\" 3)", "\"lo\"");
+        check(
+            "(string-split \"a,b,,c\" \",\")",
+            "(\"a\" \"b\" \"\" \"c\")",
+        );
+        check("(string-split \"  a b  \")", "(\"a\" \"b\")");
+        check("(string-join (list \"a\" \"b\") \", \")", "\"a, b\"");
+        check("(string-upcase \"straße\")", "\"STRASSE\"");
+        check("(string-downcase \"ÀB\")", "\"àb\"");
+        check("(string-trim \"  a b \")", "\"a b\"");
+        check("(string-replace \"aXbX\" \"X\" \"-\")", "\"a-b-\"");
+        check("(string-contains? \"hello\" \"ell\")", "true");
+        check("(string-starts-with? \"hello\" \"he\")", "true");
+        check("(string-ends-with? \"hello\" \"he\")", "false");
+        check("(string-index \"ñandú\" \"dú\")", "3");
+        check("(string-index \"hello\" \"z\")", "-1");
+        check("(string->number \" 12.5 \")", "12.5");
+        check("(string->number \"twelve\")", "false");
+        check("(string->list \"añ\")", "(#\\a #\\ñ)");
+        check("(list->string (list \"a\" \"ñ\"))", "\"añ\"");
+        check("(string-reverse \"añb\")", "\"bña\"");
+
+        assert_eq!(
+            run("(substring \"abc\" 2 5)", &mut interpreter).unwrap_err(),
+            "invalid range 2-5 for a string of length 3 in 'substring'!"
+        );
+    }
+
+    #[test]
+    fn math_natives() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("(mod 7 3)", "1");
+        check("(mod (- 7) 3)", "2");
+        check("(remainder (- 7) 3)", "-1");
+        check("(quotient (- 7) 2)", "-3");
+        check("(pow 2 10)", "1024");
+        check("(pow (- 2) 3)", "-8");
+        check("(sqrt 16)", "4");
+        check("(sqrt 9/4)", "3/2");
+        check("(sqrt 2.25)", "1.5");
+        check("(log 8 2)", "3.0");
+        check("(floor 1.5)", "1.0");
+        check("(floor 3/2)", "1");
+        check("(ceil 1.5)", "2.0");
+        check("(round 2.5)", "3.0");
+        check("(round 5/2)", "3");
+        check("(truncate (- 1.5))", "-1.0");
+        check("(abs (- 3))", "3");
+        check("(min 3 1 2)", "1");
+        check("(max 3 1 2)", "3");
+        check("(max 3 1.0 2)", "3.0");
+        check("(atan2 1 1)", &format!("{:?}", std::f64::consts::PI / 4.0));
+        check("(cos pi)", "-1.0");
+        check("(log e)", "1.0");
+
+        assert_eq!(
+            run("(sqrt (- 1))", &mut interpreter).unwrap_err(),
+            "can't take the square root of a negative number!"
+        );
+        assert_eq!(
+            run("(mod 1 0)", &mut interpreter).unwrap_err(),
+            "can't divide by zero (2nd argument to 'mod' is zero)!"
+        );
+        assert!(run("(log 0)", &mut interpreter).is_err());
+        assert!(run("(pow (- 8) 0.5)", &mut interpreter).is_err());
+        assert!(run("(asin 2)", &mut interpreter).is_err());
+        assert!(run("(define! pi 3)", &mut interpreter).is_err());
+    }
+
+    #[test]
+    fn numeric_tower() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        // Exact and inexact literals.
+        check("12", "12");
+        check("12.0", "12.0");
+        check("6/4", "3/2");
+        check("(+ -5 0x10 1_000)", "1011");
+        check("(* 2 -1.5e2)", "-300.0");
+        check("(string->number \"0b101\")", "5");
+        check("(string->number \"12abc\")", "false");
+
+        // Fixnums promote to bignums.
+        check("(* 99999999 99999999)", "9999999800000001");
+        check("(* 9223372036854775807 2)", "18446744073709551614");
+        check("(- (+ 9223372036854775807 1) 1)", "9223372036854775807");
+        check(
+            "(defun! fact (n) (if! (< n 2) 1 (* n (fact (- n 1)))))",
+            "<user-defined function>",
+        );
+        check("(fact 25)", "15511210043330985984000000");
+
+        // Division of integers gives rationals.
+        check("(/ 1 2)", "1/2");
+        check("(/ 6 3)", "2");
+        check("(+ 1/3 2/3)", "1");
+        check("(/ 2)", "1/2");
+
+        // Floats are contagious.
+        check("(+ 1/2 0.5)", "1.0");
+        check("(* 2 1.5)", "3.0");
+        check("(= 1 1.0)", "true");
+        check("(< 1/3 0.34)", "true");
+
+        // Conversions.
+        check("(exact 0.5)", "1/2");
+        check("(inexact 1/4)", "0.25");
+        check("(exact? 1/2)", "true");
+        check("(inexact? 1)", "false");
+        check("(integer? 2.0)", "true");
+        check("(integer? 1/2)", "false");
+
+        assert_eq!(
+            run("(/ 1 0)", &mut interpreter).unwrap_err(),
+            "can't divide by zero (2-th argument to '/' is zero)!"
+        );
+    }
+
+    #[test]
+    fn printing_and_reading() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check(r#""a\"b\\c\n""#, r#""a\"b\\c\n""#);
+        check(r#"(string-length "a\"b\n")"#, "4");
+        check(
+            r#"(list "tab\there" 1/2 :k true)"#,
+            r#"("tab\there" 1/2 :k true)"#,
+        );
+        check(
+            r#"(read-string (to-string (list "a\"b" (list 1.5 -2) :k false)))"#,
+            r#"("a\"b" (1.5 -2) :k false)"#,
+        );
+        check(
+            r#"(= (list "\u{1F600}\\") (read-string (to-string (list "\u{1F600}\\"))))"#,
+            "true",
+        );
+        check(r#"(write-to-string "a\"b\\c")"#, r#""\"a\\\"b\\\\c\"""#);
+        check(
+            r#"(read-string (write-to-string "a\"b\\c\n"))"#,
+            r#""a\"b\\c\n""#,
+        );
+        check(
+            r#"(= "a\"b\\c" (read-string (write-to-string "a\"b\\c")))"#,
+            "true",
+        );
+        check(r#"(write-to-string 1/2)"#, r#""1/2""#);
+
+        assert!(run(r#"(read-string "(1 2")"#, &mut interpreter).is_err());
+        assert!(run(r#"(read-string "(f 2)")"#, &mut interpreter).is_err());
+    }
+
+    #[test]
+    fn chars() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("#\\a", "#\\a");
+        check(
+            "(list #\\space #\\newline #\\u{7})",
+            "(#\\space #\\newline #\\u{7})",
+        );
+        check("(= #\\a #\\a)", "true");
+        check("(= #\\a \"a\")", "false");
+        check("(char? #\\a)", "true");
+        check("(char? \"a\")", "false");
+        check("(char->integer #\\A)", "65");
+        check("(integer->char 0x1F600)", "#\\\u{1F600}");
+        check("(char-upcase #\\ñ)", "#\\Ñ");
+        check("(char-downcase #\\A)", "#\\a");
+        check("(char-alphabetic? #\\a)", "true");
+        check("(char-numeric? #\\a)", "false");
+        check("(char-whitespace? #\\tab)", "true");
+        check("(list->string (list #\\a \"bc\" #\\d))", "\"abcd\"");
+        check("(char->string #\\a)", "\"a\"");
+        check("(read-string \"(#\\\\a)\")", "(#\\a)");
+
+        assert!(run("(integer->char 55296)", &mut interpreter).is_err());
+        assert!(run("(char-upcase \"a\")", &mut interpreter).is_err());
+    }
+
+    #[test]
+    fn maps() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("{:a (+ 1 2) \"b\" (list 1)}", "{:a 3 \"b\" (1)}");
+        check("{}", "{}");
+        check("(define! m {:a 1 :b 2})", "{:a 1 :b 2}");
+        check("(get m :a)", "1");
+        check("(get m :c)", "()");
+        check("(get m :c 0)", "0");
+        check("(assoc m :c 3 :a 0)", "{:a 0 :b 2 :c 3}");
+        check("(dissoc m :a :z)", "{:b 2}");
+        check("m", "{:a 1 :b 2}");
+        check("(keys m)", "(:a :b)");
+        check("(values m)", "(1 2)");
+        check("(contains-key? m :b)", "true");
+        check("(contains-key? m 2)", "false");
+        check(
+            "(map-merge m {:b 3} (hash-map (list 1) :x))",
+            "{:a 1 :b 3 (1) :x}",
+        );
+        check("(= {:a 1 :b 2} {:b 2 :a 1})", "true");
+        check("(= {:a 1} {:a 2})", "false");
+        check("(map? m)", "true");
+        check("(read-string (to-string {:a \"x\"}))", "{:a \"x\"}");
+
+        assert!(run("(hash-map :a)", &mut interpreter).is_err());
+    }
+
+    #[test]
+    fn sets() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("#{1 (+ 1 1) 2 1.0}", "#{1 2}");
+        check("#{}", "#{}");
+        check("(define! s (set :a :b :c))", "#{:a :b :c}");
+        check("(set? s)", "true");
+        check("(set? (list :a))", "false");
+        check("(set-add s :d :a)", "#{:a :b :c :d}");
+        check("(set-remove s :b :z)", "#{:a :c}");
+        check("s", "#{:a :b :c}");
+        check("(set-member? s :c)", "true");
+        check("(set-member? s \"c\")", "false");
+        check("(set-member? #{1 1/2} 0.5)", "true");
+        check("(union #{1 2} #{2 3} #{4})", "#{1 2 3 4}");
+        check("(union)", "#{}");
+        check("(intersection #{1 2 3} #{3 2} #{2 5 3})", "#{2 3}");
+        check("(difference #{1 2 3} #{2} #{3 4})", "#{1}");
+        check("(subset? #{2 1} #{1 2 3})", "true");
+        check("(subset? #{1 4} #{1 2 3})", "false");
+        check("(set->list #{3 1 2})", "(3 1 2)");
+        check("(= #{1 2 3} #{3 2 1})", "true");
+        check("(= #{1 2} #{1 2 3})", "false");
+        check("(= #{#{1 2} [3]} #{[3] #{2 1}})", "true");
+        check("(get {#{1 2} :x} #{2 1})", ":x");
+        check("(get {{:a 1 :b 2} :y} {:b 2 :a 1})", ":y");
+        check("(read-string \"#{1 \\\"a\\\"}\")", "#{1 \"a\"}");
+
+        assert!(run("(union #{1} (list 2))", &mut interpreter).is_err());
+    }
+
+    #[test]
+    fn equality_and_ordering() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        // Functions are equal only to themselves.
+        check("(= car car)", "true");
+        check("(equal? car cdr)", "false");
+        check("(eq? if! if!)", "true");
+        check("(defun! f (x) x)", "<user-defined function>");
+        check("(define! g f)", "<user-defined function>");
+        check("(eq? f g)", "true");
+        check("(= f (lambda! (x) x))", "false");
+        check("(defrecord! point x y)", "<record function>");
+        check("(= point-x point-x)", "true");
+        check("(= point-x point-y)", "false");
+        check("(get {car 1 f 2} g)", "2");
+        check("(set-member? (set car f) f)", "true");
+        check("(set-member? (set car f) cdr)", "false");
+
+        // Identity and structural equality.
+        check("(eq? 1 1)", "true");
+        check("(eq? 1 1.0)", "false");
+        check("(equal? 1 1.0)", "true");
+        check("(eq? \"a\" \"a\")", "false");
+        check("(define! s \"a\")", "\"a\"");
+        check("(eq? s s)", "true");
+        check("(equal? \"a\" \"a\")", "true");
+        check("(eq? () (list))", "true");
+        check("(eq? (list 1) (list 1))", "false");
+        check("(equal? (list 1 [2]) (list 1 [2]))", "true");
+        check("(eq? (point 1 2) (point 1 2))", "false");
+
+        // NaN is not = to itself, but sets and maps still find it.
+        check("(= nan nan)", "false");
+        check("(= nan 1)", "false");
+        check("(equal? nan nan)", "true");
+        check("(set-member? #{nan} nan)", "true");
+        check("(get {nan :x} nan)", ":x");
+
+        // Floats are compared with exact numbers by their exact value, so
+        // equality stays transitive beyond 2^53.
+        check("(= (+ (pow 2 53) 1) (* 1.0 (pow 2 53)))", "false");
+        check("(= (pow 2 53) (* 1.0 (pow 2 53)))", "true");
+        check("(< (pow 2 53) (+ (pow 2 53) 1.0))", "false");
+        check("(< (+ (pow 2 53) 1.0) (+ (pow 2 53) 1))", "true");
+        check(
+            "(length (set->list #{(+ (pow 2 53) 1) (pow 2 53) (* 1.0 (pow 2 53))}))",
+            "2",
+        );
+        check(
+            "(set-member? #{(+ (pow 2 53) 1) (pow 2 53)} (* 1.0 (pow 2 53)))",
+            "true",
+        );
+        check(
+            "(set-member? #{(* 1.0 (pow 2 53))} (+ (pow 2 53) 1))",
+            "false",
+        );
+        check("(get {1/2 :half} 0.5)", ":half");
+        check("(= 1/3 (/ 1.0 3))", "false");
+        check("(< (pow 10 400) inf)", "true");
+        check("(> (- (pow 10 400)) -inf)", "true");
+        check("(< 1e300 (pow 10 400))", "true");
+
+        // Total order.
+        check(
+            "(sort (list \"b\" 2 :k #\\c true (list 1) 1.5 [0] \"a\" false))",
+            "(false true 1.5 2 #\\c \"a\" \"b\" :k (1) [0])",
+        );
+        check("(sort (list nan 1 -inf 1/2))", "(-inf 1/2 1 nan)");
+        check(
+            "(sort (list (list 1 2) (list 1) (list 0 5)))",
+            "((0 5) (1) (1 2))",
+        );
+        check("(sort (list {:b 1} {:a 2} {}))", "({} {:a 2} {:b 1})");
+        check("(sort (list #{2} #{3 1}))", "(#{3 1} #{2})");
+        check(
+            "(sort (list (point 2 1) (point 1 3)))",
+            "(#<point x: 1 y: 3> #<point x: 2 y: 1>)",
+        );
+        check("(sort (list 1 1.0 2 1))", "(1 1.0 1 2)");
+        check(
+            "(sort-by (lambda! (x) (car x)) (list (list :a 1) (list 0 2)))",
+            "((0 2) (:a 1))",
+        );
+    }
+
+    #[test]
+    fn lazy_sequences() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        // Promises.
+        check("(define! p (delay! (+ 1 2)))", "<promise>");
+        check("(promise? p)", "true");
+        check("(force p)", "3");
+        check("p", "<promise 3>");
+        check("(define! count 0)", "0");
+        check("(define! q (delay! (set! count (+ count 1))))", "<promise>");
+        check("(force q)", "1");
+        check("(force q)", "1");
+        check("count", "1");
+        check("(force 5)", "5");
+        check("(force (make-promise (list 1)))", "(1)");
+        check(
+            "(defun! later (n) (delay! (* n 2)))",
+            "<user-defined function>",
+        );
+        check("(force (later 21))", "42");
+
+        // Lazy sequences.
+        check(
+            "(defun! naturals-from (n) (lazy-cons! n (naturals-from (+ n 1))))",
+            "<user-defined function>",
+        );
+        check(
+            "(define! naturals (naturals-from 0))",
+            "<lazy sequence 0 ...>",
+        );
+        check("(take naturals 5)", "(0 1 2 3 4)");
+        check("naturals", "<lazy sequence 0 1 2 3 4 ...>");
+        check("(take naturals 12)", "(0 1 2 3 4 5 6 7 8 9 10 11)");
+        check("naturals", "<lazy sequence 0 1 2 3 4 5 6 7 8 9 ...>");
+        check("(car (cdr naturals))", "1");
+        check("(lazy-seq? naturals)", "true");
+        check("(lazy-seq? (list))", "false");
+        check("(take (iterate (lambda! (x) (* x 2)) 1) 5)", "(1 2 4 8 16)");
+        check(
+            "(take (lazy-map + naturals (list 10 20 30)) 5)",
+            "(10 21 32)",
+        );
+        check(
+            "(take (lazy-filter (lambda! (x) (= (mod x 2) 0)) naturals) 3)",
+            "(0 2 4)",
+        );
+        check(
+            "(defun! sieve (s) (lazy-cons! (car s) (sieve (lazy-filter (lambda! (n) (not (= (mod n (car s)) 0))) (cdr s)))))",
+            "<user-defined function>",
+        );
+        check(
+            "(take (sieve (naturals-from 2)) 10)",
+            "(2 3 5 7 11 13 17 19 23 29)",
+        );
+        check(
+            "(define! short (lazy-cons! 1 (list 2 3)))",
+            "<lazy sequence 1 ...>",
+        );
+        check("(take short 10)", "(1 2 3)");
+        check("short", "<lazy sequence 1 2 3>");
+        check("(lazy-map + ())", "<lazy sequence ...>");
+        check(
+            "(define! big (iterate (lambda! (x) (+ x 1)) 0))",
+            "<lazy sequence 0 ...>",
+        );
+        check("(length (take big 50000))", "50000");
+        check("(define! big 0)", "0");
+
+        assert_eq!(
+            run("(car (lazy-map + ()))", &mut interpreter).unwrap_err(),
+            "can't apply 'car' to the empty lazy sequence!"
+        );
+        assert_eq!(
+            run(
+                "(car (lazy-filter (lambda! (x) 1) (list 1)))",
+                &mut interpreter
+            )
+            .unwrap_err(),
+            "predicate given to 'lazy-filter' must return a boolean!"
+        );
+        assert_eq!(
+            run("(take (lazy-cons! 1 2) 2)", &mut interpreter).unwrap_err(),
+            "tail of 'lazy-cons!' must be a list or a lazy sequence but is 2!"
+        );
+        assert!(run("(lazy-map + 1)", &mut interpreter).is_err());
+        run("(define! ready false)", &mut interpreter).unwrap();
+        run(
+            "(define! r (delay! (if! ready 7 (force r))))",
+            &mut interpreter,
+        )
+        .unwrap();
+        assert_eq!(
+            run("(force r)", &mut interpreter).unwrap_err(),
+            "promise forced again while computing its own value!"
+        );
+        assert_eq!(run("r", &mut interpreter).unwrap().to_string(), "<promise>");
+        run("(set! ready true)", &mut interpreter).unwrap();
+        assert_eq!(run("(force r)", &mut interpreter).unwrap().to_string(), "7");
+    }
+
+    #[test]
+    fn generators() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        // Values given to `next` become the values of `yield`.
+        check(
+            "(define! g (make-generator (lambda! () (let! ((a (yield 1)) (b (yield (+ a 1)))) (+ b 10)))))",
+            "<generator>",
+        );
+        check("(generator? g)", "true");
+        check("(next g)", "1");
+        check("(next g 5)", "6");
+        check("(generator-done? g)", "false");
+        check("(next g 7)", "17");
+        check("(generator-done? g)", "true");
+        check("g", "<generator done>");
+
+        // Yielding from functions called by the generator, in the middle of
+        // special forms.
+        check(
+            "(defun! count-up (n end) (if! (< n end) (let! ((_ (yield n))) (count-up (+ n 1) end)) :done))",
+            "<user-defined function>",
+        );
+        check(
+            "(define! c (make-generator (lambda! () (count-up 0 3))))",
+            "<generator>",
+        );
+        check(
+            "(list (next c) (next c) (next c) (next c))",
+            "(0 1 2 :done)",
+        );
+        check("(define! total 0)", "0");
+        check(
+            "(define! m (make-generator (lambda! () (match! (yield :first) (n :when (> (yield n) 0) (set! total (+ total (yield :body)))) (_ :other)))))",
+            "<generator>",
+        );
+        check("(next m)", ":first");
+        check("(next m 3)", "3");
+        check("(next m 1)", ":body");
+        check("(next m 10)", "10");
+        check("total", "10");
+
+        // Generators are independent, and can run one another.
+        check(
+            "(define! c1 (make-generator (lambda! () (count-up 0 10))))",
+            "<generator>",
+        );
+        check(
+            "(define! c2 (make-generator (lambda! () (count-up 100 110))))",
+            "<generator>",
+        );
+        check(
+            "(define! pairs (make-generator (lambda! () (count-up (next c1) (+ (next c2) 1)))))",
+            "<generator>",
+        );
+        check(
+            "(list (next c1) (next pairs) (next c2) (next pairs))",
+            "(0 1 101 2)",
+        );
+        check(
+            "(define! deep (make-generator (lambda! () (count-up 0 500))))",
+            "<generator>",
+        );
+        check(
+            "(fold-left (lambda! (a _) (next deep)) 0 (range 0 500))",
+            "499",
+        );
+
+        // Literals can be suspended in the middle of their elements.
+        check(
+            "(define! lit (make-generator (lambda! () (list [(yield 1) 2] {:a (yield 3)} #{(yield 4)}))))",
+            "<generator>",
+        );
+        check("(next lit)", "1");
+        check("(next lit :x)", "3");
+        check("(next lit :y)", "4");
+        check("(next lit :z)", "([:x 2] {:a :y} #{:z})");
+
+        // Errors finish the generator and don't leave its bindings behind.
+        check(
+            "(define! bad (make-generator (lambda! () (let! ((x (yield 1))) (+ x :a)))))",
+            "<generator>",
+        );
+        check("(next bad)", "1");
+        check(
+            "(define! self (make-generator (lambda! () (next self))))",
+            "<generator>",
+        );
+
+        assert!(run("(next bad 2)", &mut interpreter).is_err());
+        assert_eq!(run("x", &mut interpreter).unwrap_err(), "unboud symbol 'x'");
+        assert_eq!(
+            run("(next bad)", &mut interpreter).unwrap_err(),
+            "can't resume a finished generator!"
+        );
+        assert_eq!(
+            run("(next self)", &mut interpreter).unwrap_err(),
+            "can't resume a generator from its own body!"
+        );
+        assert_eq!(
+            run("(yield 1)", &mut interpreter).unwrap_err(),
+            "'yield' can only be called by a generator, and not from a function called by a native function or inside 'delay!', 'lazy-cons!', 'let/ec!' or 'parameterize!'!"
+        );
+        assert_eq!(
+            run(
+                "(next (make-generator (lambda! () (map yield (list 1 2)))))",
+                &mut interpreter
+            )
+            .unwrap_err(),
+            "'yield' can only be called by a generator, and not from a function called by a native function or inside 'delay!', 'lazy-cons!', 'let/ec!' or 'parameterize!'!"
+        );
+        assert_eq!(
+            run(
+                "(next (make-generator (lambda! () (parameterize! () (yield 1)))))",
+                &mut interpreter
+            )
+            .unwrap_err(),
+            "'yield' can only be called by a generator, and not from a function called by a native function or inside 'delay!', 'lazy-cons!', 'let/ec!' or 'parameterize!'!"
+        );
+
+        // Generators can't be suspended under natives or special forms which
+        // evaluate their body themselves.
+        for body in &[
+            "(map (lambda! (x) (yield x)) (list 1 2))",
+            "(filter (lambda! (x) (yield x)) (list 1 2))",
+            "(sort-by (lambda! (x) (yield x)) (list 2 1))",
+            "(apply yield (list 1))",
+            "(force (delay! (yield 1)))",
+            "(car (lazy-cons! (yield 1) ()))",
+            "(let/ec! k (yield 1))",
+        ] {
+            assert_eq!(
+                run(
+                    &format!("(next (make-generator (lambda! () {})))", body),
+                    &mut interpreter
+                )
+                .unwrap_err(),
+                "'yield' can only be called by a generator, and not from a function called by a native function or inside 'delay!', 'lazy-cons!', 'let/ec!' or 'parameterize!'!"
+            );
+        }
+        assert_eq!(
+            run("(next g)", &mut interpreter).unwrap_err(),
+            "can't resume a finished generator!"
+        );
+        assert_eq!(
+            run("(make-generator 1)", &mut interpreter).unwrap_err(),
+            "1st argument to 'make-generator' must be a function!"
+        );
+    }
+
+    #[test]
+    fn continuations() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        // Early exits from a nested `map`.
+        check(
+            "(defun! first-negative (l) (let/ec! return (map (lambda! (x) (if! (< x 0) (return x) x)) l)))",
+            "<user-defined function>",
+        );
+        check("(first-negative (list 1 -2 3 -4))", "-2");
+        check("(first-negative (list 1 2))", "(1 2)");
+        check(
+            "(defun! product (l) (call/cc (lambda! (return) (fold-left (lambda! (acc x) (if! (= x 0) (return 0) (* acc x))) 1 l))))",
+            "<user-defined function>",
+        );
+        check("(product (list 1 2 3))", "6");
+        check("(product (list 1 0 :not-reached))", "0");
+        check(
+            "(let/ec! outer (+ 1 (let/ec! inner (outer (inner 10)))))",
+            "11",
+        );
+        check(
+            "(let/ec! outer (+ 1 (let/ec! inner (map (lambda! (x) (outer x)) (list 7)))))",
+            "7",
+        );
+        check("(apply call/cc (list (lambda! (k) (+ 1 (k 5)))))", "5");
+
+        // Non-local return from deep recursion, with the environment left as
+        // it was.
+        check(
+            "(defun! find-deep (n k) (if! (= n 0) (k :found) (let! ((m (- n 1))) (find-deep m k))))",
+            "<user-defined function>",
+        );
+        check("(let/ec! k (find-deep 1000 k))", ":found");
+        check("(call/cc (lambda! (k) (find-deep 1000 k)))", ":found");
+
+        // Continuations of `call/cc` can be called many times, even after
+        // `call/cc` returned.
+        check("(define! again ())", "()");
+        check("(define! seen ())", "()");
+        check(
+            "(let! ((x (call/cc (lambda! (k) (let! ((_ (set! again k))) 0))))) (let! ((_ (set! seen (cons seen x)))) (if! (< x 3) (again (+ x 1)) x)))",
+            "3",
+        );
+        check("seen", "(0 1 2 3)");
+        check(
+            "(+ 100 (call/cc (lambda! (k) (let! ((_ (set! again k))) 100))))",
+            "200",
+        );
+        check("(again 1)", "101");
+        check("(list (again 2))", "102");
+        check("(call/cc (lambda! (k) k))", "<continuation>");
+
+        // `yield` and `call/cc` are run by the evaluator, but still are native
+        // functions for Mankai code.
+        check(
+            "(list yield call/cc)",
+            "(<native function> <native function>)",
+        );
+        check("(= call/cc call/cc)", "true");
+        check("(= yield call/cc)", "false");
+
+        // Continuations captured by functions called by natives only stand
+        // for the rest of the call, and can't be resumed once it returned.
+        // Those of `call/cc` called by natives can only escape.
+        check("(define! inside ())", "()");
+        check(
+            "(map (lambda! (x) (call/cc (lambda! (k) (let! ((_ (set! inside k))) x)))) (list 1 2))",
+            "(1 2)",
+        );
+        check("(define! direct ())", "()");
+        check(
+            "(map (lambda! (x) (let! ((v (call/cc (lambda! (k) (let! ((_ (set! inside k))) 0))))) (if! (< v 3) (inside (+ v 1)) (+ x v)))) (list 10 20))",
+            "(13 23)",
+        );
+        check(
+            "(map call/cc (list (lambda! (k) (let! ((_ (set! direct k))) 1))))",
+            "(1)",
+        );
+        check("(call/cc (lambda! (k) (+ 1 (car (map k (list 7))))))", "7");
+        check("(define! escape (let/ec! k k))", "<continuation>");
+
+        assert_eq!(
+            run("(inside 5)", &mut interpreter).unwrap_err(),
+            "can't go back into an evaluation made by a native function after it finished!"
+        );
+        assert_eq!(
+            run("(direct 2)", &mut interpreter).unwrap_err(),
+            "can't escape from 'let/ec!' or 'call/cc' after it returned!"
+        );
+        assert_eq!(
+            run("(escape 1)", &mut interpreter).unwrap_err(),
+            "can't escape from 'let/ec!' or 'call/cc' after it returned!"
+        );
+        assert_eq!(
+            run("(let/ec! k (k 1 2))", &mut interpreter).unwrap_err(),
+            "continuations require exactly one argument!"
+        );
+        assert_eq!(
+            run("(let/ec! 1 2)", &mut interpreter).unwrap_err(),
+            "1st argument to 'let/ec!' must be an identifier!"
+        );
+        assert_eq!(run("m", &mut interpreter).unwrap_err(), "unboud symbol 'm'");
+    }
+
+    #[test]
+    fn parameters() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("(define! indent (make-parameter 0))", "<parameter>");
+        check("(indent)", "0");
+        check("(parameter? indent)", "true");
+        check("(parameter? 0)", "false");
+        check("(defun! current () (indent))", "<user-defined function>");
+        check("(parameterize! ((indent 4)) (current))", "4");
+        check("(indent)", "0");
+        check(
+            "(parameterize! ((indent 1)) (parameterize! ((indent (+ (indent) 1))) (list (indent) (current))))",
+            "(2 2)",
+        );
+        check("(parameterize! ((indent 1) (indent 2)) (indent))", "2");
+        check("(indent)", "0");
+
+        // Converters apply to the starting value and to the new ones.
+        check(
+            "(define! precision (make-parameter 2 (lambda! (x) (* x 10))))",
+            "<parameter>",
+        );
+        check("(precision)", "20");
+        check("(parameterize! ((precision 3)) (precision))", "30");
+        check("(precision)", "20");
+
+        // Values are restored when leaving the body by an error or a
+        // continuation.
+        check("(let/ec! k (parameterize! ((indent 2)) (k (indent))))", "2");
+        check("(indent)", "0");
+        check(
+            "(call/cc (lambda! (k) (parameterize! ((indent 3)) (map (lambda! (x) (k (+ x (indent)))) (list 1)))))",
+            "4",
+        );
+        check("(indent)", "0");
+
+        assert_eq!(
+            run("(parameterize! ((indent 5)) (car ()))", &mut interpreter).unwrap_err(),
+            "can't apply 'car' to the empty list!"
+        );
+        assert_eq!(run("(indent)", &mut interpreter).unwrap().to_string(), "0");
+        assert_eq!(
+            run("(parameterize! ((current 1)) 2)", &mut interpreter).unwrap_err(),
+            "1-th binding of 'parameterize!' must be for a parameter!"
+        );
+        assert_eq!(
+            run("(indent 1)", &mut interpreter).unwrap_err(),
+            "parameters must be called without arguments!"
+        );
+        assert_eq!(
+            run("(make-parameter 1 2)", &mut interpreter).unwrap_err(),
+            "2nd argument to 'make-parameter' must be a function!"
+        );
+    }
+
+    #[test]
+    fn boxes_and_atoms() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        // Boxes.
+        check("(define! b (box 1))", "<box 1>");
+        check("(unbox b)", "1");
+        check("(set-box! b (list 1 2))", "(1 2)");
+        check("b", "<box (1 2)>");
+        check("(box? b)", "true");
+        check("(box? 1)", "false");
+        check(
+            "(defun! fill (x n) (set-box! x n))",
+            "<user-defined function>",
+        );
+        check("(let! ((shared b)) (fill shared 3))", "3");
+        check("(unbox b)", "3");
+        check("(= (box 1) (box 1))", "false");
+        check(
+            "(let! ((x (box 1))) (list (= x x) (eq? x x)))",
+            "(true true)",
+        );
+        check(
+            "(defun! bump (x) (set-box! x (+ (unbox x) 1)))",
+            "<user-defined function>",
+        );
+        check("(let! ((c (box 0))) (list (bump c) (bump c)))", "(1 2)");
+        // Functions made in the same `let!` share the box it binds, even
+        // after it returns, and whatever `n` is visible where they're called.
+        check(
+            "(define! counter-pair (let! ((n (box 0))) (list (lambda! () (set-box! n (+ (unbox n) 1))) (lambda! () (unbox n)))))",
+            "(<user-defined function> <user-defined function>)",
+        );
+        check(
+            "(define! inc (car counter-pair))",
+            "<user-defined function>",
+        );
+        check(
+            "(define! peek (car (cdr counter-pair)))",
+            "<user-defined function>",
+        );
+        check("(inc)", "1");
+        check("(inc)", "2");
+        check("(peek)", "2");
+        check("(let! ((n (box 100))) (list (inc) (peek)))", "(3 3)");
+        check("(set-box! b b)", "<box <box ...>>");
+
+        // Atoms.
+        check("(define! counter (atom 0))", "<atom 0>");
+        check("(defun! tick (c) (swap! c + 1))", "<user-defined function>");
+        check("(tick counter)", "1");
+        check("(tick counter)", "2");
+        check("(deref counter)", "2");
+        check("(swap! counter + 10 20)", "32");
+        check("(reset! counter 0)", "0");
+        check("counter", "<atom 0>");
+        check("(atom? counter)", "true");
+        check("(atom? b)", "false");
+        check("(= (atom 0) (atom 0))", "false");
+        check("(length (set->list (set counter counter (atom 0))))", "2");
+        check(
+            "(defun! make-counter () (let! ((a (atom 0))) (lambda! () (swap! a + 1))))",
+            "<user-defined function>",
+        );
+        check("(define! c1 (make-counter))", "<user-defined function>");
+        check("(define! c2 (make-counter))", "<user-defined function>");
+        check("(list (c1) (c1) (c2))", "(1 2 1)");
+
+        // `swap!` calls the function again if it changed the atom.
+        check("(define! calls (atom 0))", "<atom 0>");
+        check(
+            "(swap! counter (lambda! (x) (let! ((_ (swap! calls + 1))) (if! (= x 0) (let! ((_ (reset! counter 10))) -1) (+ x 1)))))",
+            "11",
+        );
+        check("(deref calls)", "2");
+        check("(reset! calls 0)", "0");
+
+        assert_eq!(run("n", &mut interpreter).unwrap_err(), "unboud symbol 'n'");
+        assert_eq!(
+            run("(deref b)", &mut interpreter).unwrap_err(),
+            "1st argument to 'deref' must be an atom!"
+        );
+        assert_eq!(
+            run("(unbox counter)", &mut interpreter).unwrap_err(),
+            "1st argument to 'unbox' must be a box!"
+        );
+        assert_eq!(
+            run("(swap! counter)", &mut interpreter).unwrap_err(),
+            "'swap!' requires at least two arguments!"
+        );
+
+        assert_eq!(
+            run(
+                "(swap! counter (lambda! (x) (let! ((_ (swap! calls + 1))) (reset! counter (+ x 1)))))",
+                &mut interpreter
+            )
+            .unwrap_err(),
+            "'swap!' gave up after 100 attempts, since the function kept changing the atom!"
+        );
+        assert_eq!(
+            run("(deref calls)", &mut interpreter).unwrap().to_string(),
+            "100"
+        );
+    }
+
+    #[test]
+    fn records() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("(defrecord! player name score)", "<record function>");
+        check(
+            "(define! bob (player \"bob\" 3))",
+            "#<player name: \"bob\" score: 3>",
+        );
+        check("(player-name bob)", "\"bob\"");
+        check("(player-score bob)", "3");
+        check(
+            "(player-with-score bob (+ (player-score bob) 1))",
+            "#<player name: \"bob\" score: 4>",
+        );
+        check("bob", "#<player name: \"bob\" score: 3>");
+        check("(player? bob)", "true");
+        check("(player? (list \"bob\" 3))", "false");
+        check("(= bob (player \"bob\" 3))", "true");
+        check("(= bob (player \"bob\" 4))", "false");
+        check("(defrecord! empty)", "<record function>");
+        check("(empty)", "#<empty>");
+        check("(get {bob 1} (player \"bob\" 3))", "1");
+        check("(defrecord! node node)", "<record function>");
+        check("(node-node (node 1))", "1");
+
+        // Redefining a type makes a new one.
+        check(
+            "(define! old (player \"bob\" 3))",
+            "#<player name: \"bob\" score: 3>",
+        );
+        check("(defrecord! player name score)", "<record function>");
+        check("(player? old)", "false");
+
+        assert_eq!(
+            run("(player \"bob\")", &mut interpreter).unwrap_err(),
+            "'player' requires exactly 2 arguments!"
+        );
+        assert_eq!(
+            run("(player-name (empty))", &mut interpreter).unwrap_err(),
+            "1st argument to 'player-name' must be a 'player' record!"
+        );
+        assert_eq!(
+            run("(defrecord! point x x)", &mut interpreter).unwrap_err(),
+            "'defrecord!' got the field 'x' twice!"
+        );
+        assert_eq!(
+            run("(defrecord! if! a)", &mut interpreter).unwrap_err(),
+            "can't assign to 'if!' because the name is reserved for a special form!"
+        );
+        assert_eq!(
+            run("(defrecord! true)", &mut interpreter).unwrap_err(),
+            "can't assign to 'true' because the name is reserved for a constant!"
+        );
+        assert_eq!(
+            run("(defrecord! string length)", &mut interpreter).unwrap_err(),
+            "can't assign to 'string?' because the name is reserved for a native function!"
+        );
+        assert_eq!(
+            run("(deftype! shape (circle r) (index of))", &mut interpreter).unwrap_err(),
+            "can't assign to 'index-of' because the name is reserved for a native function!"
+        );
+        assert_eq!(
+            run("(circle 1)", &mut interpreter).unwrap_err(),
+            "unboud symbol 'circle'"
+        );
+        assert_eq!(
+            run("(string-length \"ab\")", &mut interpreter)
+                .unwrap()
+                .to_string(),
+            "2"
+        );
+    }
+
+    #[test]
+    fn types_and_matching() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check(
+            "(deftype! shape (circle r) (rect w h))",
+            "<record function>",
+        );
+        check("(rect 2 3)", "#<rect w: 2 h: 3>");
+        check("(shape? (circle 1))", "true");
+        check("(shape? 1)", "false");
+        check("(circle? (rect 1 1))", "false");
+        check("(circle-r (circle 4))", "4");
+        check(
+            "(defun! area (s) (match! s ((circle r) (* 3 r r)) ((rect w h) (* w h))))",
+            "<user-defined function>",
+        );
+        check("(area (circle 2))", "12");
+        check("(area (rect 2 5))", "10");
+
+        // Literals, wildcards and guards.
+        check(
+            "(defun! describe (x) (match! x (0 \"zero\") (\"hi\" \"greeting\") (n :when (< n 0) \"negative\") (_ \"other\")))",
+            "<user-defined function>",
+        );
+        check("(describe 0)", "\"zero\"");
+        check("(describe -3)", "\"negative\"");
+        check("(describe \"hi\")", "\"greeting\"");
+        check("(describe 5)", "\"other\"");
+
+        // Lists, vectors and maps.
+        check(
+            "(match! (list 1 2 3) ((list a b) a) ((list a &rest r) r))",
+            "(2 3)",
+        );
+        check("(match! (list) ((list a) a) (() :empty))", ":empty");
+        check("(match! [1 [2 3]] ([x [y z]] (+ x y z)))", "6");
+        check("(match! [1 2 3] ([x &rest r] r))", "[2 3]");
+        check("(match! {:name \"bob\" :score 3} ({:score s} s))", "3");
+        check("(match! {:name \"bob\"} ({:score s} s) (_ :none))", ":none");
+        check(
+            "(match! (list true :a #\\c) ((list true :a #\\c) :yes))",
+            ":yes",
+        );
+
+        // Records and nested patterns.
+        check("(defrecord! player name score)", "<record function>");
+        check(
+            "(match! (list (player \"bob\" 3)) ((list (player name 3)) name))",
+            "\"bob\"",
+        );
+
+        // Pattern variables are bound in a new layer.
+        check("(define! r 1)", "1");
+        check("(match! (circle 5) ((circle r) r))", "5");
+        check("r", "1");
+
+        assert_eq!(
+            run("(match! (rect 1 2) ((circle r) r))", &mut interpreter).unwrap_err(),
+            "no matching clause in 'match!' for #<rect w: 1 h: 2>!"
+        );
+        assert_eq!(
+            run("(match! 1 ((car x) x))", &mut interpreter).unwrap_err(),
+            "'car' in pattern is not a record constructor!"
+        );
+        assert_eq!(
+            run("(match! (circle 1) ((circle) 1))", &mut interpreter).unwrap_err(),
+            "pattern for 'circle' must have 1 field but has 0!"
+        );
+        assert!(run("(match! 1 (x :when 1 x))", &mut interpreter).is_err());
+        assert_eq!(
+            run("(match! (list 1 2) ((list x x) x))", &mut interpreter).unwrap_err(),
+            "'x' appears more than once in the pattern (list x x)!"
+        );
+    }
+
+    #[test]
+    fn destructuring() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check(
+            "((lambda! ((list x y) z) (list x y z)) (list 1 2) 3)",
+            "(1 2 3)",
+        );
+        check("(defun! swap ([a b]) [b a])", "<user-defined function>");
+        check("(swap [1 2])", "[2 1]");
+        check(
+            "(defun! score ({:name n :score s} &optional (bonus 0)) (list n (+ s bonus)))",
+            "<user-defined function>",
+        );
+        check("(score {:name \"bob\" :score 3} 1)", "(\"bob\" 4)");
+        check(
+            "((lambda! ((list head &rest tail)) tail) (list 1 2 3))",
+            "(2 3)",
+        );
+        check(
+            "((lambda! ((list (list a _) (list b c))) (list a b c)) (list (list 1 2) (list 3 4)))",
+            "(1 3 4)",
+        );
+
+        check("(let! ((x 1) ((list y z) (list x 3))) (+ x y z))", "5");
+        check("(let! (((list) ())) 1)", "1");
+
+        // A destructuring pattern at the start of a parameter list isn't
+        // mistaken for a clause, even when the body looks like one.
+        check(
+            "(defun! pick ((list x y) first?) ((if! first? car last) (list x y)))",
+            "<user-defined function>",
+        );
+        check("(pick (list 1 2) true)", "1");
+        check("(pick (list 1 2) false)", "2");
+        check(
+            "(defun! same ((x y) x) ((a b) b))",
+            "<user-defined function>",
+        );
+        check(
+            "(let! (({:a a} {:a 1 :b 2}) ([_ b] [1 2])) (list a b))",
+            "(1 2)",
+        );
+        check("(define! x 10)", "10");
+        check("(let! ((x 1)) x)", "1");
+        check("x", "10");
+
+        assert_eq!(
+            run("(swap (list 1 2))", &mut interpreter).unwrap_err(),
+            "1-th argument to 'swap' must match the pattern [a b] but is (1 2)!"
+        );
+        assert_eq!(
+            run("(score {:name \"bob\"})", &mut interpreter).unwrap_err(),
+            "1-th argument to 'score' must match the pattern {:name n :score s} but is {:name \"bob\"}!"
+        );
+        assert_eq!(
+            run("(let! (((list a b) (list 1))) a)", &mut interpreter).unwrap_err(),
+            "value of the 1-th binding of 'let!' must match the pattern (list a b) but is (1)!"
+        );
+        assert_eq!(
+            run("(lambda! ((list a a)) a)", &mut interpreter).unwrap_err(),
+            "'a' appears more than once in the pattern (list a a)!"
+        );
+        assert_eq!(
+            run("(lambda! ((list a b) a) a)", &mut interpreter).unwrap_err(),
+            "argument 'a' appears more than once!"
+        );
+        assert_eq!(
+            run("(same 1)", &mut interpreter).unwrap_err(),
+            "found 1 arguments but 'same' expected 2 arguments!"
+        );
+        assert_eq!(
+            run("(let! (((a b) (list 1 2))) a)", &mut interpreter).unwrap_err(),
+            "invalid destructuring pattern '(a b)': only identifiers, _, (list ...), [...] and {...} can be used!"
+        );
+        assert!(run("(let! ((1 2)) 3)", &mut interpreter).is_err());
+    }
+
+    #[test]
+    fn vectors() {
+        let mut interpreter = Interpreter::new();
+        let mut check = |source: &str, expected: &str| {
+            assert_eq!(run(source, &mut interpreter).unwrap().to_string(), expected)
+        };
+
+        check("[1 (+ 1 1) \"three\"]", "[1 2 \"three\"]");
+        check("(define! v (vector 1 2 3))", "[1 2 3]");
+        check("(vector-ref v 1)", "2");
+        check("(vector-set v 1 :two)", "[1 :two 3]");
+        check("v", "[1 2 3]");
+        check("(vector-length v)", "3");
+        check("(vector->list v)", "(1 2 3)");
+        check("(list->vector (list 1 2))", "[1 2]");
+        check("(vector? v)", "true");
+        check("(vector? (list 1))", "false");
+        check("(= [1 2] [1 2])", "true");
+        check("(= [1 2] (list 1 2))", "false");
+        check("(nth v 2)", "3");
+        check("(length [])", "0");
+        check("(last v)", "3");
+        check("(index-of v 2)", "1");
+        check("(member? v 4)", "false");
+        check("(sort [3 1 2])", "[1 2 3]");
+        check("(unique [1 2 1])", "[1 2]");
+        check("(reverse v)", "[3 2 1]");
+        check("(take v 2)", "[1 2]");
+        check("(drop v 2)", "[3]");
+        check("(drop v 5)", "[]");
+        check("(map (lambda! (x) (* x 2)) v)", "[2 4 6]");
+        check("(map + v (list 10 20))", "[11 22]");
+        check("(map + (list 10 20) v)", "(11 22)");
+        check("(append v (list 4) [5])", "[1 2 3 4 5]");
+        check("(append (list 0) v)", "(0 1 2 3)");
+        check("(read-string (to-string [1 [\"a\"]]))", "[1 [\"a\"]]");
+
+        assert_eq!(
+            run("(vector-ref v 3)", &mut interpreter).unwrap_err(),
+            "index 3 is out of bounds for a vector of length 3!"
+        );
+        assert!(run("(vector-ref (list 1) 0)", &mut interpreter).is_err());
+    }
 }
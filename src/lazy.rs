@@ -0,0 +1,426 @@
+use crate::interpreter::{Interpreter, MankaiObject, RuntimeError};
+use crate::list::List;
+use crate::parser::Sexp;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Maximum number of elements shown when printing a lazy sequence.
+const PRINTED_ELEMENTS: usize = 10;
+
+/// Local bindings seen when a promise or a lazy sequence is made. Delayed
+/// computations run later, when these bindings may be gone, so they're
+/// captured and brought back into scope when the computation runs.
+type Bindings = Rc<HashMap<String, MankaiObject>>;
+
+/// Run `f` with `bindings` in a new layer of the environment.
+fn with_bindings<T>(
+    interpreter: &mut Interpreter,
+    bindings: &Bindings,
+    f: impl FnOnce(&mut Interpreter) -> Result<T, RuntimeError>,
+) -> Result<T, RuntimeError> {
+    interpreter.environment.extend_with(bindings);
+    let result = f(interpreter);
+    interpreter.environment.restrict();
+
+    result
+}
+
+/// An expression whose evaluation is delayed.
+#[derive(Clone)]
+struct Delayed {
+    body: Rc<Sexp>,
+    bindings: Bindings,
+}
+
+impl Delayed {
+    fn new(interpreter: &Interpreter, body: &Sexp) -> Self {
+        Delayed {
+            body: Rc::new(body.clone()),
+            bindings: Rc::new(interpreter.environment.locals()),
+        }
+    }
+
+    fn evaluate(&self, interpreter: &mut Interpreter) -> Result<MankaiObject, RuntimeError> {
+        with_bindings(interpreter, &self.bindings, |interpreter| {
+            interpreter.evaluate(&self.body)
+        })
+    }
+}
+
+enum PromiseState {
+    Delayed(Delayed),
+    /// The expression of the promise is being evaluated.
+    Forcing,
+    Forced(MankaiObject),
+}
+
+/// A promise made by `delay!` or `make-promise`: a value computed the first
+/// time the promise is forced and remembered afterwards.
+#[derive(Clone)]
+pub struct Promise(Rc<RefCell<PromiseState>>);
+
+impl Promise {
+    /// Make a promise to evaluate an expression.
+    pub fn delay(interpreter: &Interpreter, body: &Sexp) -> Self {
+        Promise(Rc::new(RefCell::new(PromiseState::Delayed(Delayed::new(
+            interpreter,
+            body,
+        )))))
+    }
+
+    /// Make an already forced promise.
+    pub fn forced(value: MankaiObject) -> Self {
+        Promise(Rc::new(RefCell::new(PromiseState::Forced(value))))
+    }
+
+    /// Get the value of the promise, computing it if it's the first time.
+    pub fn force(&self, interpreter: &mut Interpreter) -> Result<MankaiObject, RuntimeError> {
+        let delayed = match &*self.0.borrow() {
+            PromiseState::Forced(value) => return Ok(value.clone()),
+            PromiseState::Delayed(delayed) => delayed.clone(),
+            PromiseState::Forcing => {
+                return Err(RuntimeError::new(
+                    "promise forced again while computing its own value!",
+                ))
+            }
+        };
+
+        // Mark the promise so that forcing it from its own expression fails
+        // instead of recursing forever.
+        *self.0.borrow_mut() = PromiseState::Forcing;
+        match delayed.evaluate(interpreter) {
+            Ok(value) => {
+                *self.0.borrow_mut() = PromiseState::Forced(value.clone());
+                Ok(value)
+            }
+            Err(error) => {
+                // The promise can be forced again later.
+                *self.0.borrow_mut() = PromiseState::Delayed(delayed);
+                Err(error)
+            }
+        }
+    }
+
+    /// Check if two promises are the same object.
+    pub fn ptr_eq(&self, other: &Promise) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Address of the promise, identifying it.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+}
+
+impl std::fmt::Display for Promise {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &*self.0.borrow() {
+            PromiseState::Delayed(_) | PromiseState::Forcing => write!(f, "<promise>"),
+            PromiseState::Forced(value) => write!(f, "<promise {}>", value.to_string()),
+        }
+    }
+}
+
+/// How to compute the elements of a lazy sequence which haven't been realized
+/// yet.
+#[derive(Clone)]
+enum Thunk {
+    /// Evaluate an expression giving a list or a lazy sequence, as the tail
+    /// of `lazy-cons!`.
+    Expression(Delayed),
+    /// The elements of a list.
+    List(List),
+    /// Apply a function to a value, then to the result and so on (the value
+    /// itself has already been given).
+    Iterate {
+        function: MankaiObject,
+        value: MankaiObject,
+        bindings: Bindings,
+    },
+    /// Apply a function to the elements of some sequences taken in parallel.
+    Map {
+        function: MankaiObject,
+        sequences: Vec<LazySeq>,
+        bindings: Bindings,
+    },
+    /// Keep the elements of a sequence satisfying a predicate.
+    Filter {
+        predicate: MankaiObject,
+        sequence: LazySeq,
+        bindings: Bindings,
+    },
+}
+
+enum Cell {
+    Pending(Thunk),
+    Empty,
+    Cons(MankaiObject, LazySeq),
+}
+
+/// A lazy sequence: its elements are computed when they're first needed, then
+/// remembered. It can be infinite.
+#[derive(Clone)]
+pub struct LazySeq(Rc<RefCell<Cell>>);
+
+impl LazySeq {
+    fn new(cell: Cell) -> Self {
+        LazySeq(Rc::new(RefCell::new(cell)))
+    }
+
+    fn pending(thunk: Thunk) -> Self {
+        LazySeq::new(Cell::Pending(thunk))
+    }
+
+    /// Make a sequence starting with `head` and continuing with the list or
+    /// lazy sequence `tail` evaluates to, evaluating it only when needed.
+    pub fn cons(interpreter: &Interpreter, head: MankaiObject, tail: &Sexp) -> Self {
+        LazySeq::new(Cell::Cons(
+            head,
+            LazySeq::pending(Thunk::Expression(Delayed::new(interpreter, tail))),
+        ))
+    }
+
+    /// Make a sequence with the elements of a list.
+    pub fn from_list(list: List) -> Self {
+        LazySeq::pending(Thunk::List(list))
+    }
+
+    /// Make the infinite sequence `value`, `(function value)`,
+    /// `(function (function value))`, ...
+    pub fn iterate(interpreter: &Interpreter, function: MankaiObject, value: MankaiObject) -> Self {
+        LazySeq::new(Cell::Cons(
+            value.clone(),
+            LazySeq::pending(Thunk::Iterate {
+                function,
+                value,
+                bindings: Rc::new(interpreter.environment.locals()),
+            }),
+        ))
+    }
+
+    /// Make the sequence of the results of applying `function` to the
+    /// elements of `sequences` taken in parallel, ending with the shortest.
+    pub fn map(interpreter: &Interpreter, function: MankaiObject, sequences: Vec<LazySeq>) -> Self {
+        LazySeq::pending(Thunk::Map {
+            function,
+            sequences,
+            bindings: Rc::new(interpreter.environment.locals()),
+        })
+    }
+
+    /// Make the sequence of the elements of `sequence` satisfying
+    /// `predicate`.
+    pub fn filter(interpreter: &Interpreter, predicate: MankaiObject, sequence: LazySeq) -> Self {
+        LazySeq::pending(Thunk::Filter {
+            predicate,
+            sequence,
+            bindings: Rc::new(interpreter.environment.locals()),
+        })
+    }
+
+    /// First element and rest of the sequence, or `None` if it's empty. The
+    /// first element is computed if it hasn't been yet.
+    pub fn split(
+        &self,
+        interpreter: &mut Interpreter,
+    ) -> Result<Option<(MankaiObject, LazySeq)>, RuntimeError> {
+        let thunk = match &*self.0.borrow() {
+            Cell::Empty => return Ok(None),
+            Cell::Cons(head, tail) => return Ok(Some((head.clone(), tail.clone()))),
+            Cell::Pending(thunk) => thunk.clone(),
+        };
+
+        let split = realize(interpreter, thunk)?;
+        *self.0.borrow_mut() = match &split {
+            Some((head, tail)) => Cell::Cons(head.clone(), tail.clone()),
+            None => Cell::Empty,
+        };
+
+        Ok(split)
+    }
+
+    /// List of the first `n` elements (or fewer if the sequence is shorter).
+    pub fn take(&self, interpreter: &mut Interpreter, n: usize) -> Result<List, RuntimeError> {
+        let mut list = List::new();
+        let mut sequence = self.clone();
+        while list.len() < n {
+            match sequence.split(interpreter)? {
+                Some((head, tail)) => {
+                    list.push(head);
+                    sequence = tail;
+                }
+                None => break,
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// Check if two lazy sequences are the same object.
+    pub fn ptr_eq(&self, other: &LazySeq) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Address of the sequence, identifying it.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+
+    /// Take the tail out of the sequence if nothing else refers to it.
+    fn unlink(&self) -> Option<LazySeq> {
+        if Rc::strong_count(&self.0) != 1 {
+            return None;
+        }
+
+        match self.0.try_borrow_mut() {
+            Ok(mut cell) => match std::mem::replace(&mut *cell, Cell::Empty) {
+                Cell::Cons(_, tail) => Some(tail),
+                _ => None,
+            },
+            Err(_) => None,
+        }
+    }
+}
+
+/// Compute the first element and the rest of a sequence.
+fn realize(
+    interpreter: &mut Interpreter,
+    thunk: Thunk,
+) -> Result<Option<(MankaiObject, LazySeq)>, RuntimeError> {
+    match thunk {
+        Thunk::Expression(delayed) => match delayed.evaluate(interpreter)? {
+            MankaiObject::List(list) => realize(interpreter, Thunk::List(list)),
+            MankaiObject::LazySeq(sequence) => sequence.split(interpreter),
+            value => Err(RuntimeError::new(&format!(
+                "tail of 'lazy-cons!' must be a list or a lazy sequence but is {}!",
+                value.to_string()
+            ))),
+        },
+        Thunk::List(list) => Ok(list
+            .first()
+            .map(|first| (first.clone(), LazySeq::from_list(list.rest())))),
+        Thunk::Iterate {
+            function,
+            value,
+            bindings,
+        } => {
+            let next = with_bindings(interpreter, &bindings, |interpreter| {
+                function.call(interpreter, vec![value])
+            })?;
+
+            Ok(Some((
+                next.clone(),
+                LazySeq::pending(Thunk::Iterate {
+                    function,
+                    value: next,
+                    bindings,
+                }),
+            )))
+        }
+        Thunk::Map {
+            function,
+            sequences,
+            bindings,
+        } => {
+            let mut heads = Vec::new();
+            let mut tails = Vec::new();
+            for sequence in &sequences {
+                match sequence.split(interpreter)? {
+                    Some((head, tail)) => {
+                        heads.push(head);
+                        tails.push(tail);
+                    }
+                    None => return Ok(None),
+                }
+            }
+
+            let value = with_bindings(interpreter, &bindings, |interpreter| {
+                function.call(interpreter, heads)
+            })?;
+
+            Ok(Some((
+                value,
+                LazySeq::pending(Thunk::Map {
+                    function,
+                    sequences: tails,
+                    bindings,
+                }),
+            )))
+        }
+        Thunk::Filter {
+            predicate,
+            mut sequence,
+            bindings,
+        } => loop {
+            let (head, tail) = match sequence.split(interpreter)? {
+                Some(split) => split,
+                None => return Ok(None),
+            };
+
+            let keep = with_bindings(interpreter, &bindings, |interpreter| {
+                predicate.call(interpreter, vec![head.clone()])
+            })?;
+            match keep {
+                MankaiObject::Bool(true) => {
+                    return Ok(Some((
+                        head,
+                        LazySeq::pending(Thunk::Filter {
+                            predicate,
+                            sequence: tail,
+                            bindings,
+                        }),
+                    )))
+                }
+                MankaiObject::Bool(false) => sequence = tail,
+                _ => {
+                    return Err(RuntimeError::new(
+                        "predicate given to 'lazy-filter' must return a boolean!",
+                    ))
+                }
+            }
+        },
+    }
+}
+
+impl Drop for LazySeq {
+    /// Drop the realized elements one by one rather than recursively, so that
+    /// long sequences don't overflow the stack.
+    fn drop(&mut self) {
+        let mut next = self.unlink();
+        while let Some(sequence) = next {
+            next = sequence.unlink();
+        }
+    }
+}
+
+impl std::fmt::Display for LazySeq {
+    /// Show the elements realized so far (up to `PRINTED_ELEMENTS`) without
+    /// computing any new one, followed by `...` if there may be more.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<lazy sequence")?;
+        let mut sequence = self.clone();
+        let mut shown = 0;
+        loop {
+            let tail = match &*sequence.0.borrow() {
+                Cell::Empty => None,
+                Cell::Cons(head, tail) if shown < PRINTED_ELEMENTS => {
+                    write!(f, " {}", head.to_string())?;
+                    Some(tail.clone())
+                }
+                _ => {
+                    write!(f, " ...")?;
+                    None
+                }
+            };
+            match tail {
+                Some(tail) => {
+                    sequence = tail;
+                    shown += 1;
+                }
+                None => break,
+            }
+        }
+        write!(f, ">")
+    }
+}
@@ -1,3 +1,4 @@
+use crate::number::Number;
 use crate::token::*;
 
 /// A lexing error.
@@ -61,18 +62,10 @@ impl Lexer {
         }
     }
 
-    /// Peek the character adter the next one.
-    fn peek_next(&self) -> char {
-        if self.is_at_end() {
-            '\0'
-        } else {
-            self.source.chars().nth(self.current + 1).unwrap()
-        }
-    }
-
-    /// Return if the character is a separator (whitespace or parenthesis).
+    /// Return if the character is a separator (whitespace, parenthesis, brace,
+    /// bracket or the start of a comment).
     fn is_separator(c: char) -> bool {
-        c.is_whitespace() || c == '(' || c == ')'
+        c.is_whitespace() || "(){}[];".contains(c)
     }
 
     /// Add a new token to the internal store with the given kind.
@@ -86,61 +79,156 @@ impl Lexer {
         self.tokens.push(Token::new(lexeme, kind));
     }
 
-    /// Tokenize a string.
+    /// Tokenize a string, decoding its escape sequences.
     fn finish_string(&mut self) -> Result<(), ScanError> {
+        let mut string = String::new();
+
         loop {
             if self.is_at_end() {
                 return Err(ScanError::new("unfinished string", self.start));
             }
 
-            let next = self.advance();
-
-            // Skip quoted characters, break if we hit the end of the string.
-            if next == '\\' {
-                self.current += 1;
-            } else if next == '"' {
-                break;
+            // Break if we hit the end of the string, decode escape sequences.
+            match self.advance() {
+                '"' => break,
+                '\\' => {
+                    let escape_start = self.current - 1;
+                    if self.is_at_end() {
+                        return Err(ScanError::new("unfinished string", self.start));
+                    }
+
+                    match self.advance() {
+                        'n' => string.push('\n'),
+                        't' => string.push('\t'),
+                        '\\' => string.push('\\'),
+                        '"' => string.push('"'),
+                        'u' => string.push(self.finish_unicode_escape(escape_start)?),
+                        c => {
+                            return Err(ScanError::new(
+                                &format!("unknown escape sequence '\\{}'", c),
+                                escape_start,
+                            ))
+                        }
+                    }
+                }
+                c => string.push(c),
             }
         }
 
-        let string: String = self
-            .source
-            .chars()
-            .skip(self.start + 1)
-            .take(self.current - self.start - 2)
-            .collect();
         self.add_token(TokenKind::String(string));
 
         Ok(())
     }
 
-    /// Tokenize a number.
+    /// Decode the `{...}` part of a `\u{...}` escape sequence (1 to 6
+    /// hexadecimal digits).
+    fn finish_unicode_escape(&mut self, escape_start: usize) -> Result<char, ScanError> {
+        let error = ScanError::new("invalid unicode escape sequence", escape_start);
+
+        if self.peek() != '{' {
+            return Err(error);
+        }
+        self.current += 1;
+
+        let mut digits = String::new();
+        while self.peek().is_ascii_hexdigit() && digits.len() < 6 {
+            digits.push(self.advance());
+        }
+
+        if self.peek() != '}' || digits.is_empty() {
+            return Err(error);
+        }
+        self.current += 1;
+
+        match std::char::from_u32(u32::from_str_radix(&digits, 16).unwrap()) {
+            Some(c) => Ok(c),
+            None => Err(error),
+        }
+    }
+
+    /// Tokenize a number. See `Number::parse` for the accepted literals.
     fn finish_number(&mut self) -> Result<(), ScanError> {
-        // Consume the non-decimal part.
-        while self.peek().is_digit(10) {
+        while !Lexer::is_separator(self.peek()) && !self.is_at_end() {
             self.current += 1;
         }
 
-        // Check if the number has a decimal part.
-        if self.peek() == '.' && self.peek_next().is_digit(10) {
-            // Consume the '.'.
-            self.current += 1;
+        let lexeme: String = self
+            .source
+            .chars()
+            .skip(self.start)
+            .take(self.current - self.start)
+            .collect();
+        match Number::parse(&lexeme) {
+            Some(number) => self.add_token(TokenKind::Number(number)),
+            None => {
+                return Err(ScanError::new(
+                    &format!("malformed number '{}'", lexeme),
+                    self.start,
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tokenize a character literal: `#\\` followed by a character, a
+    /// character name (`space`, `newline`, `tab`) or a `u{...}` code.
+    fn finish_char(&mut self) -> Result<(), ScanError> {
+        if self.is_at_end() {
+            return Err(ScanError::new("expected character after '#\\'", self.start));
+        }
 
-            // Consume the decimal part.
-            while self.peek().is_digit(10) {
+        // The first character is always part of the literal, even if it's a
+        // separator (e.g. `#\\(`).
+        let first = self.advance();
+        if first == 'u' && self.peek() == '{' {
+            // Consume the braces of the code, which are separators.
+            while self.peek() != '}' && !self.peek().is_whitespace() && !self.is_at_end() {
+                self.current += 1;
+            }
+            if self.peek() == '}' {
+                self.current += 1;
+            }
+        } else {
+            while !Lexer::is_separator(self.peek()) && !self.is_at_end() {
                 self.current += 1;
             }
         }
 
-        let number: f64 = self
+        let name: String = self
             .source
             .chars()
-            .skip(self.start)
-            .take(self.current - self.start)
-            .collect::<String>()
-            .parse()
-            .unwrap();
-        self.add_token(TokenKind::Number(number));
+            .skip(self.start + 2)
+            .take(self.current - self.start - 2)
+            .collect();
+        let c = match name.as_str() {
+            "space" => ' ',
+            "newline" => '\n',
+            "tab" => '\t',
+            _ if name.chars().count() == 1 => name.chars().next().unwrap(),
+            _ if name.starts_with("u{") && name.ends_with('}') => {
+                match u32::from_str_radix(&name[2..name.len() - 1], 16)
+                    .ok()
+                    .filter(|_| name.len() <= 9)
+                    .and_then(std::char::from_u32)
+                {
+                    Some(c) => c,
+                    None => {
+                        return Err(ScanError::new(
+                            "invalid unicode character literal",
+                            self.start,
+                        ))
+                    }
+                }
+            }
+            _ => {
+                return Err(ScanError::new(
+                    &format!("unknown character name '{}'", name),
+                    self.start,
+                ))
+            }
+        };
+        self.add_token(TokenKind::Char(c));
 
         Ok(())
     }
@@ -151,7 +239,77 @@ impl Lexer {
             self.current += 1;
         }
 
-        self.add_token(TokenKind::Identifier);
+        let lexeme: String = self
+            .source
+            .chars()
+            .skip(self.start)
+            .take(self.current - self.start)
+            .collect();
+
+        // The special floats are spelled like identifiers.
+        match lexeme.as_str() {
+            "inf" | "+inf" | "-inf" | "nan" | "+nan" | "-nan" => {
+                self.add_token(TokenKind::Number(Number::parse(&lexeme).unwrap()))
+            }
+            _ => self.add_token(TokenKind::Identifier),
+        }
+
+        Ok(())
+    }
+
+    /// Tokenize a keyword (`:name`).
+    fn finish_keyword(&mut self) -> Result<(), ScanError> {
+        while !Lexer::is_separator(self.peek()) && !self.is_at_end() {
+            self.current += 1;
+        }
+
+        if self.current - self.start == 1 {
+            return Err(ScanError::new(
+                "expected keyword name after ':'",
+                self.start,
+            ));
+        }
+
+        let name: String = self
+            .source
+            .chars()
+            .skip(self.start + 1)
+            .take(self.current - self.start - 1)
+            .collect();
+        self.add_token(TokenKind::Keyword(name));
+
+        Ok(())
+    }
+
+    /// Skip a line comment.
+    fn skip_line_comment(&mut self) {
+        while self.peek() != '\n' && !self.is_at_end() {
+            self.current += 1;
+        }
+    }
+
+    /// Skip a (possibly nested) block comment, the opening `#|` has already
+    /// been consumed.
+    fn skip_block_comment(&mut self) -> Result<(), ScanError> {
+        let mut depth = 1;
+
+        while depth > 0 {
+            if self.is_at_end() {
+                return Err(ScanError::new("unterminated block comment", self.start));
+            }
+
+            match self.advance() {
+                '#' if self.peek() == '|' => {
+                    self.current += 1;
+                    depth += 1;
+                }
+                '|' if self.peek() == '#' => {
+                    self.current += 1;
+                    depth -= 1;
+                }
+                _ => (),
+            }
+        }
 
         Ok(())
     }
@@ -169,9 +327,49 @@ impl Lexer {
                 self.add_token(TokenKind::RightParen);
                 Ok(())
             }
+            '{' => {
+                self.add_token(TokenKind::LeftBrace);
+                Ok(())
+            }
+            '}' => {
+                self.add_token(TokenKind::RightBrace);
+                Ok(())
+            }
+            '[' => {
+                self.add_token(TokenKind::LeftBracket);
+                Ok(())
+            }
+            ']' => {
+                self.add_token(TokenKind::RightBracket);
+                Ok(())
+            }
             '"' => self.finish_string(),
+            ';' => {
+                self.skip_line_comment();
+                Ok(())
+            }
+            '#' if self.peek() == '|' => {
+                self.current += 1;
+                self.skip_block_comment()
+            }
+            '#' if self.peek() == '\\' => {
+                self.current += 1;
+                self.finish_char()
+            }
+            '#' if self.peek() == '{' => {
+                self.current += 1;
+                self.add_token(TokenKind::HashLeftBrace);
+                Ok(())
+            }
+            '#' if self.peek() == ';' => {
+                self.current += 1;
+                self.add_token(TokenKind::DatumComment);
+                Ok(())
+            }
+            ':' => self.finish_keyword(),
             _ => {
-                if c.is_digit(10) {
+                // Signs are part of a number only if a digit follows them.
+                if c.is_ascii_digit() || ((c == '-' || c == '+') && self.peek().is_ascii_digit()) {
                     self.finish_number()
                 } else {
                     self.finish_identifier()
@@ -196,7 +394,8 @@ impl Lexer {
 
 #[cfg(test)]
 mod lexer_test {
-    use super::{Lexer, Token, TokenKind};
+    use super::{Lexer, Number, Token, TokenKind};
+    use crate::bigint::BigInt;
 
     #[test]
     fn lexer_initialization_and_basic_operations() {
@@ -214,7 +413,9 @@ mod lexer_test {
 
     #[test]
     fn lexing() {
-        let mut lexer = Lexer::new(String::from("(*bar+ \"foo\" baz) 64.333 12 foo 12,2"));
+        let mut lexer = Lexer::new(String::from(
+            "(*bar+ \"foo\" baz) 64.333 12 foo 12 99999999999999999999 6/4",
+        ));
         let mut token;
 
         if let Err(err) = lexer.scan() {
@@ -225,12 +426,24 @@ mod lexer_test {
         assert_eq!(token, Token::new(String::from(""), TokenKind::Eof));
 
         token = lexer.tokens.pop().unwrap();
-        assert_eq!(token, Token::new(String::from(",2"), TokenKind::Identifier));
+        assert_eq!(
+            token.kind,
+            TokenKind::Number(Number::from_ratio(BigInt::from_i64(3), BigInt::from_i64(2)))
+        );
+        assert_eq!(token.lexeme, "6/4");
+
+        token = lexer.tokens.pop().unwrap();
+        assert_eq!(
+            token.kind,
+            TokenKind::Number(Number::Bignum(
+                BigInt::parse("99999999999999999999", 10).unwrap()
+            ))
+        );
 
         token = lexer.tokens.pop().unwrap();
         assert_eq!(
             token,
-            Token::new(String::from("12"), TokenKind::Number(12.0))
+            Token::new(String::from("12"), TokenKind::Number(Number::Fixnum(12)))
         );
 
         token = lexer.tokens.pop().unwrap();
@@ -242,13 +455,16 @@ mod lexer_test {
         token = lexer.tokens.pop().unwrap();
         assert_eq!(
             token,
-            Token::new(String::from("12"), TokenKind::Number(12.0))
+            Token::new(String::from("12"), TokenKind::Number(Number::Fixnum(12)))
         );
 
         token = lexer.tokens.pop().unwrap();
         assert_eq!(
             token,
-            Token::new(String::from("64.333"), TokenKind::Number(64.333))
+            Token::new(
+                String::from("64.333"),
+                TokenKind::Number(Number::Float(64.333))
+            )
         );
 
         token = lexer.tokens.pop().unwrap();
@@ -278,4 +494,186 @@ mod lexer_test {
         token = lexer.tokens.pop().unwrap();
         assert_eq!(token, Token::new(String::from("("), TokenKind::LeftParen));
     }
+
+    #[test]
+    fn keywords() {
+        let mut lexer = Lexer::new(String::from("(f :foo)"));
+
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+
+        assert_eq!(
+            lexer.tokens.get(2).unwrap().clone(),
+            Token::new(
+                String::from(":foo"),
+                TokenKind::Keyword(String::from("foo"))
+            )
+        );
+
+        lexer = Lexer::new(String::from("(f : foo)"));
+        if lexer.scan().is_ok() {
+            panic!("expected lexing error for an empty keyword!");
+        }
+    }
+
+    #[test]
+    fn numbers() {
+        let numbers = [
+            ("-5", Number::from(-5)),
+            ("+5", Number::from(5)),
+            ("1_000_000", Number::from(1_000_000)),
+            ("0xff", Number::from(255)),
+            ("-0o17", Number::from(-15)),
+            ("0b1010", Number::from(10)),
+            ("1e10", Number::Float(1e10)),
+            ("2.5E-3", Number::Float(2.5e-3)),
+            ("-1.5e+2", Number::Float(-150.0)),
+            ("-inf", Number::Float(f64::NEG_INFINITY)),
+            ("inf", Number::Float(f64::INFINITY)),
+        ];
+
+        for (source, number) in numbers.iter() {
+            let mut lexer = Lexer::new(String::from(*source));
+            if let Err(err) = lexer.scan() {
+                panic!("{}", err.message);
+            }
+
+            assert_eq!(
+                lexer.tokens.first().unwrap().clone(),
+                Token::new(String::from(*source), TokenKind::Number(number.clone()))
+            );
+        }
+
+        // NaN is not equal to itself.
+        let mut lexer = Lexer::new(String::from("nan"));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+        match &lexer.tokens.first().unwrap().kind {
+            TokenKind::Number(Number::Float(n)) if n.is_nan() => (),
+            kind => panic!("expected nan, found {:?}", kind),
+        }
+
+        // Signs alone are identifiers.
+        lexer = Lexer::new(String::from("(- -foo)"));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+        assert_eq!(
+            lexer.tokens.get(1).unwrap().clone(),
+            Token::new(String::from("-"), TokenKind::Identifier)
+        );
+        assert_eq!(
+            lexer.tokens.get(2).unwrap().clone(),
+            Token::new(String::from("-foo"), TokenKind::Identifier)
+        );
+
+        for source in [
+            "12abc", "12,2", "1__0", "1_", "0x", "0b102", "1/0", "1.", "1e",
+        ]
+        .iter()
+        {
+            let mut lexer = Lexer::new(String::from(*source));
+            match lexer.scan() {
+                Ok(()) => panic!("expected lexing error for '{}'!", source),
+                Err(err) => assert_eq!(err.message, format!("malformed number '{}'", source)),
+            }
+        }
+    }
+
+    #[test]
+    fn string_escapes() {
+        let mut lexer = Lexer::new(String::from(r#""a\"b\\c\n\t\u{41}\u{1F600}""#));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+
+        assert_eq!(
+            lexer.tokens.first().unwrap().kind,
+            TokenKind::String(String::from("a\"b\\c\n\tA\u{1F600}"))
+        );
+
+        for source in [
+            r#""\q""#,
+            r#""\u{}""#,
+            r#""\u{D800}""#,
+            r#""\u41""#,
+            r#""\"#,
+        ]
+        .iter()
+        {
+            let mut lexer = Lexer::new(String::from(*source));
+            if lexer.scan().is_ok() {
+                panic!("expected lexing error for {}!", source);
+            }
+        }
+    }
+
+    #[test]
+    fn comments() {
+        let mut lexer = Lexer::new(String::from(
+            "(foo ; line comment\n bar;baz\n #| block #| nested |# comment |# #;qux)",
+        ));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+
+        let kinds: Vec<TokenKind> = lexer.tokens.into_iter().map(|token| token.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::LeftParen,
+                TokenKind::Identifier,
+                TokenKind::Identifier,
+                TokenKind::DatumComment,
+                TokenKind::Identifier,
+                TokenKind::RightParen,
+                TokenKind::Eof
+            ]
+        );
+
+        let mut lexer = Lexer::new(String::from("foo #| #| |# bar"));
+        match lexer.scan() {
+            Ok(()) => panic!("expected lexing error for an unterminated block comment!"),
+            Err(err) => {
+                assert_eq!(err.message, "unterminated block comment");
+                assert_eq!(err.position, 4);
+            }
+        }
+    }
+
+    #[test]
+    fn chars() {
+        let mut lexer = Lexer::new(String::from(
+            "(#\\a #\\space #\\newline #\\tab #\\u{1F600} #\\( #\\))",
+        ));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+
+        let kinds: Vec<TokenKind> = lexer.tokens.into_iter().map(|token| token.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::LeftParen,
+                TokenKind::Char('a'),
+                TokenKind::Char(' '),
+                TokenKind::Char('\n'),
+                TokenKind::Char('\t'),
+                TokenKind::Char('\u{1F600}'),
+                TokenKind::Char('('),
+                TokenKind::Char(')'),
+                TokenKind::RightParen,
+                TokenKind::Eof
+            ]
+        );
+
+        for source in ["#\\", "#\\foo", "#\\u{D800}", "#\\u{}"].iter() {
+            let mut lexer = Lexer::new(String::from(*source));
+            if lexer.scan().is_ok() {
+                panic!("expected lexing error for '{}'!", source);
+            }
+        }
+    }
 }
@@ -1,15 +1,41 @@
+mod bigint;
+mod dynamic;
 mod environment;
 mod error;
+mod generator;
 mod interpreter;
+mod lazy;
 mod lexer;
+mod list;
+mod machine;
+mod map;
 mod native_functions;
+mod number;
+mod parameters;
 mod parser;
+mod pattern;
+mod record;
+mod reference;
+mod set;
 mod special_forms;
 mod token;
 
+pub use bigint::*;
+pub use dynamic::*;
 pub use environment::*;
 pub use error::*;
+pub use generator::*;
 pub use interpreter::*;
+pub use lazy::*;
 pub use lexer::*;
+pub use list::*;
+pub use machine::*;
+pub use map::*;
+pub use number::*;
+pub use parameters::*;
 pub use parser::*;
+pub use pattern::*;
+pub use record::*;
+pub use reference::*;
+pub use set::*;
 pub use token::*;
@@ -0,0 +1,350 @@
+use std::rc::Rc;
+
+use crate::interpreter::MankaiObject;
+
+/// Number of bits of an index consumed by each level of the tree.
+const BITS: usize = 5;
+/// Number of children of each node of the tree.
+const WIDTH: usize = 1 << BITS;
+
+/// A node of the tree holding the elements of a list.
+enum Node {
+    Branch(Vec<Rc<Node>>),
+    Leaf(Vec<MankaiObject>),
+}
+
+/// A persistent immutable list: "modifying" a list makes a new one sharing
+/// most of its structure with the original.
+///
+/// Elements are stored in a tree of nodes with up to 32 children, with the
+/// last (up to 32) elements kept apart in a tail. Appending an element copies
+/// at most one path of the tree, getting an element walks one path and
+/// dropping the first element just moves the start of the list, so `cons`,
+/// `car` and `cdr` take (practically) constant time.
+#[derive(Clone)]
+pub struct List {
+    /// Number of elements stored, including the ones dropped from the front.
+    size: usize,
+    /// Number of elements dropped from the front.
+    start: usize,
+    /// Number of index bits below the root.
+    shift: usize,
+    /// Root of the tree holding all the elements but the tail.
+    root: Rc<Node>,
+    /// Last elements of the list.
+    tail: Rc<Vec<MankaiObject>>,
+}
+
+impl List {
+    /// Make a new empty list.
+    pub fn new() -> Self {
+        List {
+            size: 0,
+            start: 0,
+            shift: BITS,
+            root: Rc::new(Node::Branch(Vec::new())),
+            tail: Rc::new(Vec::new()),
+        }
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.size - self.start
+    }
+
+    /// Check if the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Index of the first element stored in the tail.
+    fn tail_offset(&self) -> usize {
+        if self.size < WIDTH {
+            0
+        } else {
+            ((self.size - 1) >> BITS) << BITS
+        }
+    }
+
+    /// Element at the given position.
+    pub fn get(&self, index: usize) -> Option<&MankaiObject> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let index = index + self.start;
+        if index >= self.tail_offset() {
+            return self.tail.get(index & (WIDTH - 1));
+        }
+
+        let mut node = &self.root;
+        let mut level = self.shift;
+        loop {
+            match node.as_ref() {
+                Node::Branch(children) => {
+                    node = &children[(index >> level) & (WIDTH - 1)];
+                    level -= BITS;
+                }
+                Node::Leaf(elements) => return elements.get(index & (WIDTH - 1)),
+            }
+        }
+    }
+
+    /// First element.
+    pub fn first(&self) -> Option<&MankaiObject> {
+        self.get(0)
+    }
+
+    /// Last element.
+    pub fn last(&self) -> Option<&MankaiObject> {
+        match self.len() {
+            0 => None,
+            len => self.get(len - 1),
+        }
+    }
+
+    /// The list without its first element (the empty list stays empty).
+    pub fn rest(&self) -> List {
+        let mut rest = self.clone();
+        if !rest.is_empty() {
+            rest.start += 1;
+        }
+
+        rest
+    }
+
+    /// Append an element at the end of the list.
+    pub fn push(&mut self, value: MankaiObject) {
+        // There's room in the tail.
+        if self.size - self.tail_offset() < WIDTH {
+            Rc::make_mut(&mut self.tail).push(value);
+            self.size += 1;
+            return;
+        }
+
+        // Move the full tail into the tree, adding a level on top if the root
+        // is full.
+        let tail = std::mem::replace(&mut self.tail, Rc::new(vec![value]));
+        let tail = Rc::new(Node::Leaf(
+            Rc::try_unwrap(tail).unwrap_or_else(|tail| (*tail).clone()),
+        ));
+        if (self.size >> BITS) > (1 << self.shift) {
+            let path = new_path(self.shift, tail);
+            self.root = Rc::new(Node::Branch(vec![self.root.clone(), path]));
+            self.shift += BITS;
+        } else {
+            self.root = push_tail(self.size, self.shift, &self.root, tail);
+        }
+        self.size += 1;
+    }
+
+    /// Check if a value is an element of the list.
+    pub fn contains(&self, value: &MankaiObject) -> bool {
+        self.iter().any(|element| element == value)
+    }
+
+    /// Check if two lists are the same object: copies of one list, or empty.
+    pub fn ptr_eq(&self, other: &List) -> bool {
+        (self.is_empty() && other.is_empty())
+            || (Rc::ptr_eq(&self.root, &other.root)
+                && Rc::ptr_eq(&self.tail, &other.tail)
+                && self.start == other.start
+                && self.size == other.size)
+    }
+
+    /// Iterate over the elements.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            list: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    /// Copy the elements to a vector.
+    pub fn to_vec(&self) -> Vec<MankaiObject> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl Default for List {
+    fn default() -> Self {
+        List::new()
+    }
+}
+
+/// Make a path of branches of the given height leading to `node`.
+fn new_path(level: usize, node: Rc<Node>) -> Rc<Node> {
+    if level == 0 {
+        node
+    } else {
+        Rc::new(Node::Branch(vec![new_path(level - BITS, node)]))
+    }
+}
+
+/// Copy of `parent` with the leaf `tail` added as the element number `size`
+/// (`size` is the number of elements before the push).
+fn push_tail(size: usize, level: usize, parent: &Rc<Node>, tail: Rc<Node>) -> Rc<Node> {
+    let mut children = match parent.as_ref() {
+        Node::Branch(children) => children.clone(),
+        Node::Leaf(_) => unreachable!("leaves are never parents"),
+    };
+    let index = ((size - 1) >> level) & (WIDTH - 1);
+
+    let child = if level == BITS {
+        tail
+    } else {
+        match children.get(index) {
+            Some(child) => push_tail(size, level - BITS, child, tail),
+            None => new_path(level - BITS, tail),
+        }
+    };
+
+    if index < children.len() {
+        children[index] = child;
+    } else {
+        children.push(child);
+    }
+
+    Rc::new(Node::Branch(children))
+}
+
+/// Iterator over the elements of a list.
+#[derive(Clone)]
+pub struct Iter<'a> {
+    list: &'a List,
+    /// Position of the next element from the front.
+    front: usize,
+    /// Position after the next element from the back.
+    back: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a MankaiObject;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        self.front += 1;
+        self.list.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        self.list.get(self.back)
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+impl<'a> IntoIterator for &'a List {
+    type Item = &'a MankaiObject;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl std::iter::FromIterator<MankaiObject> for List {
+    fn from_iter<I: IntoIterator<Item = MankaiObject>>(iter: I) -> Self {
+        let mut list = List::new();
+        for value in iter {
+            list.push(value);
+        }
+
+        list
+    }
+}
+
+impl From<Vec<MankaiObject>> for List {
+    fn from(vector: Vec<MankaiObject>) -> Self {
+        vector.into_iter().collect()
+    }
+}
+
+impl std::ops::Index<usize> for List {
+    type Output = MankaiObject;
+
+    fn index(&self, index: usize) -> &MankaiObject {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!(
+                "index {} is out of bounds for a list of length {}",
+                index,
+                self.len()
+            ),
+        }
+    }
+}
+
+impl std::fmt::Debug for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for List {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+#[cfg(test)]
+mod list_test {
+    use super::List;
+    use crate::interpreter::MankaiObject;
+    use crate::number::Number;
+
+    fn number(n: usize) -> MankaiObject {
+        MankaiObject::Number(Number::from(n as i64))
+    }
+
+    #[test]
+    fn push_get_and_rest() {
+        let mut list = List::new();
+        for i in 0..5000 {
+            list.push(number(i));
+        }
+
+        assert_eq!(list.len(), 5000);
+        for i in 0..5000 {
+            assert_eq!(list.get(i), Some(&number(i)));
+        }
+        assert_eq!(list.get(5000), None);
+
+        // Older versions are not affected by pushes on newer ones.
+        let old = list.clone();
+        list.push(number(5000));
+        assert_eq!(old.len(), 5000);
+        assert_eq!(list.last(), Some(&number(5000)));
+
+        // Dropping elements from the front.
+        let mut rest = list.clone();
+        for i in 0..1000 {
+            assert_eq!(rest.first(), Some(&number(i)));
+            rest = rest.rest();
+        }
+        assert_eq!(rest.len(), 4001);
+        rest.push(number(5001));
+        assert_eq!(rest.iter().next_back(), Some(&number(5001)));
+        assert_eq!(rest.iter().count(), 4002);
+        assert_eq!(list.len(), 5001);
+
+        assert_eq!(list.rest().rest(), (2..5001).map(number).collect());
+        assert!(List::new().rest().is_empty());
+    }
+}
@@ -0,0 +1,707 @@
+use crate::interpreter::{enter_clause, Interpreter, MankaiObject, RuntimeError, SpecialForm};
+use crate::list::List;
+use crate::map::Map;
+use crate::parser::Sexp;
+use crate::pattern::Pattern;
+use crate::set::Set;
+use crate::special_forms;
+use crate::token::Token;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// What's left to do with the value of the expression being evaluated. The
+/// evaluator keeps these frames on a stack of its own rather than recursing
+/// on Rust's, so that a generator can be suspended in the middle of an
+/// evaluation and resumed later, and so that `call/cc` can capture the rest
+/// of an evaluation by copying the stack.
+///
+/// Calls, vector, map and set literals and the special forms `define!`,
+/// `if!`, `let!`, `match!` and `set!` are run with frames, as are the bodies
+/// of functions. Other special forms
+/// and native functions evaluate expressions with `Interpreter::evaluate`,
+/// which runs a stack of its own.
+#[derive(Clone)]
+enum Frame {
+    /// Evaluating the elements of the call `list`: first the callee (`None`
+    /// until evaluated), then the arguments.
+    Call {
+        list: Rc<Vec<Sexp>>,
+        callee: Option<MankaiObject>,
+        arguments: Vec<MankaiObject>,
+    },
+    /// Evaluating the elements of the vector, map or set literal `literal`, in
+    /// order (for a map, each key then its value), `values` holding those
+    /// evaluated so far.
+    Literal {
+        literal: Sexp,
+        values: Vec<MankaiObject>,
+    },
+    /// Choosing the branch of the `if!` form `list` from its condition.
+    If(Rc<Vec<Sexp>>),
+    /// Binding the value of the `index`-th binding of the `let!` form `list`
+    /// in the layer of the environment made for it.
+    Let {
+        list: Rc<Vec<Sexp>>,
+        bindings: Rc<Vec<(Pattern, Sexp)>>,
+        index: usize,
+    },
+    /// Matching a value against the clauses of the `match!` form `list`.
+    Match(Rc<Vec<Sexp>>),
+    /// Checking the guard of the clause at position `index` in the `match!`
+    /// form `list`, whose pattern matched `value`, in the layer of the
+    /// environment made for the clause.
+    Guard {
+        list: Rc<Vec<Sexp>>,
+        value: MankaiObject,
+        index: usize,
+    },
+    /// Binding a value with `define!`.
+    Define(Token),
+    /// Assigning a value with `set!`.
+    Set(Token),
+    /// Leaving the layer of the environment made for a function call, a
+    /// `let!` or a clause of `match!`, once its body is evaluated.
+    Restrict,
+}
+
+impl Frame {
+    /// Check if the frame owns the innermost layer of the environment, which
+    /// must be removed if an error unwinds the frame.
+    fn owns_layer(&self) -> bool {
+        matches!(
+            self,
+            Frame::Let { .. } | Frame::Guard { .. } | Frame::Restrict
+        )
+    }
+}
+
+/// An evaluation suspended by `yield`: the frames left to run and the layers
+/// of the environment added since the evaluation started.
+pub struct Suspension {
+    frames: Vec<Frame>,
+    layers: Vec<HashMap<String, MankaiObject>>,
+}
+
+/// How an evaluation that `yield` can suspend ended.
+pub enum Outcome {
+    /// The evaluation finished with a value.
+    Finished(MankaiObject),
+    /// The evaluation yielded a value and can be resumed.
+    Yielded(MankaiObject, Suspension),
+}
+
+/// A continuation made by `call/cc` or `let/ec!`: calling it with a value
+/// makes the evaluation it was captured in go on from there, with that value.
+#[derive(Clone)]
+pub struct Continuation(Rc<Captured>);
+
+enum Captured {
+    /// Made by `let/ec!` (or by `call/cc` when called by a native function):
+    /// it can only be used to escape from its body, while the body runs.
+    Escape {
+        /// Whether the body is still running.
+        active: Cell<bool>,
+    },
+    /// Made by `call/cc`: the frames left in the run of the evaluator it was
+    /// captured in, and the layers of the environment added since the run
+    /// started.
+    Full {
+        /// Identifier of the run.
+        run: usize,
+        /// Whether the run was the outermost one. Such continuations can go
+        /// on from any outermost run, even after their own has finished.
+        outermost: bool,
+        frames: Vec<Frame>,
+        layers: Vec<HashMap<String, MankaiObject>>,
+    },
+}
+
+/// A call to a continuation, carried by a `RuntimeError` out of the native
+/// functions and runs of the evaluator it's made from, up to `let/ec!` or to
+/// the run it goes on in.
+pub struct Jump {
+    continuation: Continuation,
+    /// Identifier of the run to go on in, for continuations made by `call/cc`.
+    run: Option<usize>,
+    /// Value given to the continuation.
+    value: MankaiObject,
+}
+
+impl Continuation {
+    /// Make an escape continuation, active until `catch` is called.
+    pub fn escape() -> Self {
+        Continuation(Rc::new(Captured::Escape {
+            active: Cell::new(true),
+        }))
+    }
+
+    /// Call the continuation with arguments: always an error, which is a jump
+    /// if the continuation can be used.
+    pub fn call(
+        &self,
+        interpreter: &Interpreter,
+        mut arguments: Vec<MankaiObject>,
+    ) -> Result<MankaiObject, RuntimeError> {
+        // Check arity.
+        if arguments.len() != 1 {
+            return Err(RuntimeError::new(
+                "continuations require exactly one argument!",
+            ));
+        }
+
+        let run =
+            match &*self.0 {
+                Captured::Escape { active } if active.get() => None,
+                Captured::Escape { .. } => {
+                    return Err(RuntimeError::new(
+                        "can't escape from 'let/ec!' or 'call/cc' after it returned!",
+                    ))
+                }
+                Captured::Full { run, .. } if interpreter.runs.contains(run) => Some(*run),
+                Captured::Full {
+                    outermost: true, ..
+                } => interpreter.runs.first().copied(),
+                Captured::Full { .. } => return Err(RuntimeError::new(
+                    "can't go back into an evaluation made by a native function after it finished!",
+                )),
+            };
+
+        Err(RuntimeError {
+            message: String::from("continuation called outside of its evaluation!"),
+            jump: Some(Box::new(Jump {
+                continuation: self.clone(),
+                run,
+                value: arguments.pop().unwrap(),
+            })),
+        })
+    }
+
+    /// Deactivate an escape continuation once its body has returned `result`,
+    /// and get the value given to the continuation if `result` is a jump to
+    /// it.
+    pub fn catch(
+        &self,
+        result: Result<MankaiObject, RuntimeError>,
+    ) -> Result<MankaiObject, RuntimeError> {
+        if let Captured::Escape { active } = &*self.0 {
+            active.set(false);
+        }
+
+        match result {
+            Err(RuntimeError {
+                jump: Some(jump), ..
+            }) if jump.continuation.ptr_eq(self) => Ok(jump.value),
+            result => result,
+        }
+    }
+
+    /// Check if two continuations are the same object.
+    pub fn ptr_eq(&self, other: &Continuation) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Address of the continuation, identifying it.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+}
+
+/// A run of the evaluator.
+struct Run {
+    /// Identifier of the run.
+    id: usize,
+    /// Depth of the environment when the run started.
+    base: usize,
+    /// Whether `yield` can suspend the run.
+    can_yield: bool,
+}
+
+/// What to do after giving a value to a frame.
+enum Step {
+    /// Give this value to the frame on top of the stack.
+    Continue(MankaiObject),
+    /// Suspend the evaluation, yielding this value.
+    Yield(MankaiObject),
+}
+
+impl Interpreter {
+    /// Evaluate an expression.
+    pub fn evaluate(&mut self, expr: &Sexp) -> Result<MankaiObject, RuntimeError> {
+        let outcome = self.run(false, |interpreter, frames| interpreter.enter(expr, frames));
+
+        match outcome? {
+            Outcome::Finished(value) => Ok(value),
+            Outcome::Yielded(..) => unreachable!("only generators can be suspended"),
+        }
+    }
+
+    /// Call a function without arguments in an evaluation that `yield` can
+    /// suspend.
+    pub fn start(&mut self, function: &MankaiObject) -> Result<Outcome, RuntimeError> {
+        self.run(true, |interpreter, frames| {
+            interpreter.apply(function.clone(), Vec::new(), frames)
+        })
+    }
+
+    /// Resume a suspended evaluation, `value` being the value of the call to
+    /// `yield` which suspended it.
+    pub fn resume(
+        &mut self,
+        suspension: Suspension,
+        value: MankaiObject,
+    ) -> Result<Outcome, RuntimeError> {
+        self.run(true, |interpreter, frames| {
+            interpreter.environment.restore(suspension.layers);
+            *frames = suspension.frames;
+            Ok(value)
+        })
+    }
+
+    /// Run the evaluator: `start` pushes the first frames and returns the value
+    /// to give them.
+    fn run(
+        &mut self,
+        can_yield: bool,
+        start: impl FnOnce(&mut Interpreter, &mut Vec<Frame>) -> Result<MankaiObject, RuntimeError>,
+    ) -> Result<Outcome, RuntimeError> {
+        self.run_count += 1;
+        let run = Run {
+            id: self.run_count,
+            base: self.environment.depth(),
+            can_yield,
+        };
+        self.runs.push(run.id);
+
+        let mut frames = Vec::new();
+        let value = start(self, &mut frames);
+        let outcome = self.run_frames(&run, frames, value);
+
+        self.runs.pop();
+        outcome
+    }
+
+    /// Give `value` to the frames, from the top of the stack down, until
+    /// there's none left or `yield` suspends the run.
+    fn run_frames(
+        &mut self,
+        run: &Run,
+        mut frames: Vec<Frame>,
+        mut value: Result<MankaiObject, RuntimeError>,
+    ) -> Result<Outcome, RuntimeError> {
+        loop {
+            let step = match value {
+                Ok(value) => match frames.pop() {
+                    Some(frame) => self.give(run, frame, value, &mut frames),
+                    None => return Ok(Outcome::Finished(value)),
+                },
+                Err(err) => {
+                    // Remove the layers of the environment added by the frames.
+                    for _ in frames.iter().filter(|frame| frame.owns_layer()) {
+                        self.environment.restrict();
+                    }
+
+                    // Go on from a continuation captured in this run, or
+                    // leave it.
+                    match err.jump {
+                        Some(jump) if jump.run == Some(run.id) => {
+                            if let Captured::Full {
+                                frames: captured,
+                                layers,
+                                ..
+                            } = &*jump.continuation.0
+                            {
+                                self.environment.restore(layers.clone());
+                                frames = captured.clone();
+                            }
+
+                            value = Ok(jump.value);
+                            continue;
+                        }
+                        jump => {
+                            return Err(RuntimeError {
+                                message: err.message,
+                                jump,
+                            })
+                        }
+                    }
+                }
+            };
+
+            value = match step {
+                Ok(Step::Continue(value)) => Ok(value),
+                Ok(Step::Yield(value)) => {
+                    let layers = self.environment.split_off(run.base);
+                    return Ok(Outcome::Yielded(value, Suspension { frames, layers }));
+                }
+                Err(err) => Err(err),
+            };
+        }
+    }
+
+    /// Start evaluating an expression: push the frames evaluating it if it's
+    /// a call, and return the value to give to the frame on top of the stack.
+    fn enter(
+        &mut self,
+        expr: &Sexp,
+        frames: &mut Vec<Frame>,
+    ) -> Result<MankaiObject, RuntimeError> {
+        match expr {
+            Sexp::Atom(token) => self.evaluate_atom(token),
+            // The empty list evaluates to itself.
+            Sexp::List(list) if list.is_empty() => Ok(MankaiObject::List(List::new())),
+            Sexp::List(list) => {
+                frames.push(Frame::Call {
+                    list: list.clone(),
+                    callee: None,
+                    arguments: Vec::new(),
+                });
+                self.enter(&list[0], frames)
+            }
+            Sexp::Map(_) | Sexp::Vector(_) | Sexp::Set(_) => {
+                self.next_element(expr.clone(), Vec::new(), frames)
+            }
+        }
+    }
+
+    /// Give a value to a frame popped from the stack. A frame owning a layer
+    /// of the environment removes it if it fails.
+    fn give(
+        &mut self,
+        run: &Run,
+        frame: Frame,
+        value: MankaiObject,
+        frames: &mut Vec<Frame>,
+    ) -> Result<Step, RuntimeError> {
+        // Run special forms as soon as they're found in the callee position.
+        // `yield` and `call/cc` get their arguments evaluated, like functions.
+        if let (
+            Frame::Call {
+                list, callee: None, ..
+            },
+            MankaiObject::SpecialForm(form),
+        ) = (&frame, &value)
+        {
+            if !matches!(form, SpecialForm::Yield | SpecialForm::CallCc) {
+                return self.special_form(*form, list, frames).map(Step::Continue);
+            }
+        }
+
+        let value = match frame {
+            Frame::Call {
+                list,
+                mut callee,
+                mut arguments,
+            } => {
+                if callee.is_none() {
+                    callee = Some(value);
+                } else {
+                    arguments.push(value);
+                }
+
+                // Evaluate the next argument.
+                let next = arguments.len() + 1;
+                if next < list.len() {
+                    frames.push(Frame::Call {
+                        list: list.clone(),
+                        callee,
+                        arguments,
+                    });
+                    return self.enter(&list[next], frames).map(Step::Continue);
+                }
+
+                // Call the function, suspend the run on `yield` or capture
+                // the rest of the run on `call/cc`.
+                let callee = callee.unwrap();
+                let form = match &callee {
+                    MankaiObject::SpecialForm(form) => Some(*form),
+                    _ => None,
+                };
+
+                if run.can_yield && matches!(form, Some(SpecialForm::Yield)) {
+                    if arguments.len() != 1 {
+                        return Err(RuntimeError::new("'yield' requires exactly one argument!"));
+                    }
+
+                    return Ok(Step::Yield(arguments.pop().unwrap()));
+                }
+
+                if matches!(form, Some(SpecialForm::CallCc)) && arguments.len() == 1 {
+                    let continuation = Continuation(Rc::new(Captured::Full {
+                        run: run.id,
+                        outermost: self.runs.len() == 1,
+                        frames: frames.clone(),
+                        layers: self.environment.copy_layers(run.base),
+                    }));
+                    let function = arguments.pop().unwrap();
+                    let arguments = vec![MankaiObject::Continuation(continuation)];
+
+                    return self.apply(function, arguments, frames).map(Step::Continue);
+                }
+
+                self.apply(callee, arguments, frames)?
+            }
+            Frame::Literal {
+                literal,
+                mut values,
+            } => {
+                values.push(value);
+                self.next_element(literal, values, frames)?
+            }
+            Frame::If(list) => match value {
+                MankaiObject::Bool(true) => self.enter(&list[2], frames)?,
+                MankaiObject::Bool(false) => self.enter(&list[3], frames)?,
+                _ => {
+                    return Err(RuntimeError::new(
+                        "1st argument to 'if!' must evaluate to a boolean!",
+                    ))
+                }
+            },
+            Frame::Let {
+                list,
+                bindings,
+                index,
+            } => {
+                if let Err(err) =
+                    special_forms::bind_pattern(self, index + 1, &bindings[index].0, &value)
+                {
+                    self.environment.restrict();
+                    return Err(err);
+                }
+
+                self.bind_next(list, bindings, index + 1, frames)?
+            }
+            Frame::Match(list) => self.match_clauses(list, value, 2, frames)?,
+            Frame::Guard {
+                list,
+                value: matched,
+                index,
+            } => match value {
+                MankaiObject::Bool(true) => {
+                    let (_, _, body) = special_forms::parse_match_clause(index, &list[index])?;
+                    frames.push(Frame::Restrict);
+                    self.enter(body, frames)?
+                }
+                MankaiObject::Bool(false) => {
+                    self.environment.restrict();
+                    self.match_clauses(list, matched, index + 1, frames)?
+                }
+                _ => {
+                    self.environment.restrict();
+                    return Err(RuntimeError::new(
+                        "guards of 'match!' must evaluate to a boolean!",
+                    ));
+                }
+            },
+            Frame::Define(name) => {
+                self.environment.define(&name, value.clone());
+                value
+            }
+            Frame::Set(name) => {
+                self.environment.set(&name, value.clone())?;
+                value
+            }
+            Frame::Restrict => {
+                self.environment.restrict();
+                value
+            }
+        };
+
+        Ok(Step::Continue(value))
+    }
+
+    /// Start running a special form, given the call `list`.
+    fn special_form(
+        &mut self,
+        form: SpecialForm,
+        list: &Rc<Vec<Sexp>>,
+        frames: &mut Vec<Frame>,
+    ) -> Result<MankaiObject, RuntimeError> {
+        let arguments = &list[1..];
+
+        match form {
+            SpecialForm::Function(function) => function(self, arguments.iter().collect()),
+            SpecialForm::Yield | SpecialForm::CallCc => {
+                unreachable!("'yield' and 'call/cc' are called like functions")
+            }
+            SpecialForm::Define => {
+                let name = special_forms::assigned_name(self, arguments)?;
+                frames.push(Frame::Define(name.clone()));
+                self.enter(&arguments[1], frames)
+            }
+            SpecialForm::Set => {
+                let name = special_forms::assigned_name(self, arguments)?;
+                frames.push(Frame::Set(name.clone()));
+                self.enter(&arguments[1], frames)
+            }
+            SpecialForm::If => {
+                // Arity check.
+                if arguments.len() != 3 {
+                    return Err(RuntimeError::new("'if!' requires exactly three arguments!"));
+                }
+
+                frames.push(Frame::If(list.clone()));
+                self.enter(&arguments[0], frames)
+            }
+            SpecialForm::Let => {
+                let bindings = special_forms::parse_let_bindings(arguments)?;
+
+                // Bind the values in a new layer of the environment, in order,
+                // then evaluate the body there.
+                self.environment.extend();
+                self.bind_next(list.clone(), Rc::new(bindings), 0, frames)
+            }
+            SpecialForm::Match => {
+                // Arity check.
+                if arguments.len() < 2 {
+                    return Err(RuntimeError::new(
+                        "'match!' requires a value followed by at least one clause!",
+                    ));
+                }
+
+                frames.push(Frame::Match(list.clone()));
+                self.enter(&arguments[0], frames)
+            }
+        }
+    }
+
+    /// Evaluate the next element of a vector, map or set literal, given the
+    /// values of the previous ones, or make the value of the literal if they
+    /// are all evaluated.
+    fn next_element(
+        &mut self,
+        literal: Sexp,
+        values: Vec<MankaiObject>,
+        frames: &mut Vec<Frame>,
+    ) -> Result<MankaiObject, RuntimeError> {
+        let index = values.len();
+        let next = match &literal {
+            Sexp::Map(entries) => {
+                entries
+                    .get(index / 2)
+                    .map(|(key, value)| if index % 2 == 1 { value } else { key })
+            }
+            Sexp::Vector(elements) | Sexp::Set(elements) => elements.get(index),
+            _ => unreachable!("only literals have elements to evaluate"),
+        };
+
+        if let Some(next) = next {
+            frames.push(Frame::Literal {
+                literal: literal.clone(),
+                values,
+            });
+            return self.enter(next, frames);
+        }
+
+        Ok(match literal {
+            Sexp::Map(_) => {
+                let mut map = Map::new();
+                let mut values = values.into_iter();
+                while let (Some(key), Some(value)) = (values.next(), values.next()) {
+                    map.insert(key, value);
+                }
+
+                MankaiObject::Map(map)
+            }
+            Sexp::Vector(_) => MankaiObject::Vector(Rc::new(values)),
+            _ => {
+                let mut set = Set::new();
+                for value in values {
+                    set.insert(value);
+                }
+
+                MankaiObject::Set(set)
+            }
+        })
+    }
+
+    /// Evaluate the value of the `index`-th binding of the `let!` form
+    /// `list`, or its body if all the values are bound.
+    fn bind_next(
+        &mut self,
+        list: Rc<Vec<Sexp>>,
+        bindings: Rc<Vec<(Pattern, Sexp)>>,
+        index: usize,
+        frames: &mut Vec<Frame>,
+    ) -> Result<MankaiObject, RuntimeError> {
+        if index < bindings.len() {
+            frames.push(Frame::Let {
+                list,
+                bindings: bindings.clone(),
+                index,
+            });
+            self.enter(&bindings[index].1, frames)
+        } else {
+            frames.push(Frame::Restrict);
+            self.enter(&list[2], frames)
+        }
+    }
+
+    /// Match a value against the clauses of the `match!` form `list`, from
+    /// the one at position `from`, and evaluate the guard or the body of the
+    /// first one that matches with the variables of its pattern bound in a new
+    /// layer of the environment.
+    fn match_clauses(
+        &mut self,
+        list: Rc<Vec<Sexp>>,
+        value: MankaiObject,
+        from: usize,
+        frames: &mut Vec<Frame>,
+    ) -> Result<MankaiObject, RuntimeError> {
+        for index in from..list.len() {
+            let (pattern, guard, body) = special_forms::parse_match_clause(index, &list[index])?;
+
+            let mut bindings = Vec::new();
+            if !Pattern::parse(pattern)?.matches(self, &value, &mut bindings)? {
+                continue;
+            }
+
+            self.environment.extend();
+            for (identifier, value) in bindings {
+                self.environment.define(&identifier, value);
+            }
+
+            return match guard {
+                Some(guard) => {
+                    frames.push(Frame::Guard {
+                        list: list.clone(),
+                        value,
+                        index,
+                    });
+                    self.enter(guard, frames)
+                }
+                None => {
+                    frames.push(Frame::Restrict);
+                    self.enter(body, frames)
+                }
+            };
+        }
+
+        Err(RuntimeError::new(&format!(
+            "no matching clause in 'match!' for {}!",
+            value.to_string()
+        )))
+    }
+
+    /// Call a function with arguments. The body of a user-defined function is
+    /// evaluated with frames.
+    fn apply(
+        &mut self,
+        callee: MankaiObject,
+        arguments: Vec<MankaiObject>,
+        frames: &mut Vec<Frame>,
+    ) -> Result<MankaiObject, RuntimeError> {
+        match &callee {
+            MankaiObject::Function {
+                name,
+                clauses,
+                captured,
+            } => {
+                let clause = enter_clause(self, name, clauses, captured, arguments)?;
+                frames.push(Frame::Restrict);
+                self.enter(&clause.body, frames)
+            }
+            _ => callee.call(self, arguments),
+        }
+    }
+}
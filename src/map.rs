@@ -0,0 +1,79 @@
+use crate::interpreter::MankaiObject;
+use std::rc::Rc;
+
+/// An insertion-ordered map from Mankai values to Mankai values. Keys are
+/// compared with Mankai equality.
+#[derive(Clone, Default)]
+pub struct Map {
+    /// Key-value pairs, in insertion order and without duplicate keys. They're
+    /// shared between copies of the map until one of them is modified.
+    entries: Rc<Vec<(MankaiObject, MankaiObject)>>,
+}
+
+impl Map {
+    /// Make a new empty map.
+    pub fn new() -> Self {
+        Map::default()
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Value associated to a key.
+    pub fn get(&self, key: &MankaiObject) -> Option<&MankaiObject> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Check if a key is in the map.
+    pub fn contains_key(&self, key: &MankaiObject) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Associate a value to a key. If the key is already in the map its value
+    /// is replaced but its position is kept.
+    pub fn insert(&mut self, key: MankaiObject, value: MankaiObject) {
+        let entries = Rc::make_mut(&mut self.entries);
+        match entries.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = value,
+            None => entries.push((key, value)),
+        }
+    }
+
+    /// Remove a key (and its value) from the map.
+    pub fn remove(&mut self, key: &MankaiObject) {
+        if self.contains_key(key) {
+            Rc::make_mut(&mut self.entries).retain(|(k, _)| k != key);
+        }
+    }
+
+    /// Check if two maps are the same object, i.e. copies of one map.
+    pub fn ptr_eq(&self, other: &Map) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries)
+    }
+
+    /// Iterate over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&MankaiObject, &MankaiObject)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+}
+
+impl PartialEq for Map {
+    /// Two maps are equal if they have the same keys associated to equal
+    /// values, no matter the insertion order.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
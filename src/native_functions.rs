@@ -1,20 +1,300 @@
-use crate::interpreter::{MankaiObject, RuntimeError};
+use crate::bigint::BigInt;
+use crate::dynamic::Parameter;
+use crate::generator::Generator;
+use crate::interpreter::{Interpreter, MankaiObject, RuntimeError, SpecialForm};
+use crate::lazy::{LazySeq, Promise};
+use crate::lexer::Lexer;
+use crate::list::List;
+use crate::machine::Continuation;
+use crate::map::Map;
+use crate::number::{Number, Rounding};
+use crate::parser::{Parser, Sexp};
+use crate::reference::Reference;
+use crate::set::Set;
+use crate::token::TokenKind;
+use std::rc::Rc;
+
+/// Maximum number of elements of a list made by `range`.
+const MAX_RANGE_LENGTH: usize = 1_000_000;
+
+/// Maximum number of times `swap!` calls its function before giving up.
+const MAX_SWAP_ATTEMPTS: usize = 100;
+
+// Helpers.
+
+/// Call `predicate` on `value` and check that it returns a boolean.
+fn call_predicate(
+    interpreter: &mut Interpreter,
+    predicate: &MankaiObject,
+    value: &MankaiObject,
+    name: &str,
+) -> Result<bool, RuntimeError> {
+    match predicate.call(interpreter, vec![value.clone()])? {
+        MankaiObject::Bool(b) => Ok(b),
+        _ => Err(RuntimeError::new(&format!(
+            "predicate given to '{}' must return a boolean!",
+            name
+        ))),
+    }
+}
+
+/// Get the list given as `position`-th argument to `name`.
+fn list_argument<'a>(
+    arguments: &'a [MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<&'a List, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::List(list) => Ok(list),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a list!",
+            position, name
+        ))),
+    }
+}
+
+/// A list or a vector, for the functions accepting both.
+enum Sequence<'a> {
+    List(&'a List),
+    Vector(&'a [MankaiObject]),
+}
+
+impl<'a> Sequence<'a> {
+    /// Number of elements.
+    fn len(&self) -> usize {
+        match self {
+            Sequence::List(list) => list.len(),
+            Sequence::Vector(vector) => vector.len(),
+        }
+    }
+
+    /// Element at the given position.
+    fn get(&self, index: usize) -> Option<&'a MankaiObject> {
+        match self {
+            Sequence::List(list) => list.get(index),
+            Sequence::Vector(vector) => vector.get(index),
+        }
+    }
+
+    /// Last element.
+    fn last(&self) -> Option<&'a MankaiObject> {
+        match self {
+            Sequence::List(list) => list.last(),
+            Sequence::Vector(vector) => vector.last(),
+        }
+    }
+
+    /// Position of the first element equal to `value`.
+    fn position(&self, value: &MankaiObject) -> Option<usize> {
+        self.iter().position(|element| element == value)
+    }
+
+    /// Iterate over the elements.
+    fn iter(&self) -> Box<dyn Iterator<Item = &'a MankaiObject> + 'a> {
+        match self {
+            Sequence::List(list) => Box::new(list.iter()),
+            Sequence::Vector(vector) => Box::new(vector.iter()),
+        }
+    }
+
+    /// Make a sequence of the same kind (list or vector) out of some elements.
+    fn same_kind(&self, elements: Vec<MankaiObject>) -> MankaiObject {
+        match self {
+            Sequence::List(_) => MankaiObject::List(elements.into()),
+            Sequence::Vector(_) => MankaiObject::Vector(Rc::new(elements)),
+        }
+    }
+}
+
+/// Get the list or vector given as `position`-th argument to `name`.
+fn sequence_argument<'a>(
+    arguments: &'a [MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<Sequence<'a>, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::List(list) => Ok(Sequence::List(list)),
+        MankaiObject::Vector(vector) => Ok(Sequence::Vector(vector)),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a list or a vector!",
+            position, name
+        ))),
+    }
+}
+
+/// Get the number given as `position`-th argument to `name`.
+fn number_argument<'a>(
+    arguments: &'a [MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<&'a Number, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::Number(n) => Ok(n),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a number!",
+            position, name
+        ))),
+    }
+}
+
+/// Get the string given as `position`-th argument to `name`.
+fn string_argument<'a>(
+    arguments: &'a [MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<&'a str, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::String(s) => Ok(s),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a string!",
+            position, name
+        ))),
+    }
+}
+
+/// Get the map given as `position`-th argument to `name`.
+fn map_argument<'a>(
+    arguments: &'a [MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<&'a Map, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::Map(map) => Ok(map),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a map!",
+            position, name
+        ))),
+    }
+}
+
+/// Get the set given as `position`-th argument to `name`.
+fn set_argument<'a>(
+    arguments: &'a [MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<&'a Set, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::Set(set) => Ok(set),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a set!",
+            position, name
+        ))),
+    }
+}
+
+/// Get the lazy sequence given as `position`-th argument to `name`. A list is
+/// turned into a lazy sequence.
+fn lazy_argument(
+    arguments: &[MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<LazySeq, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::LazySeq(sequence) => Ok(sequence.clone()),
+        MankaiObject::List(list) => Ok(LazySeq::from_list(list.clone())),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a list or a lazy sequence!",
+            position, name
+        ))),
+    }
+}
+
+/// Get the character given as `position`-th argument to `name`.
+fn char_argument(
+    arguments: &[MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<char, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::Char(c) => Ok(*c),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a character!",
+            position, name
+        ))),
+    }
+}
+
+/// Get the non-negative integer given as `position`-th argument to `name`.
+fn index_argument(
+    arguments: &[MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<usize, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::Number(n) => match n.to_i64() {
+            Some(n) if n >= 0 => Ok(n as usize),
+            _ => Err(RuntimeError::new(&format!(
+                "{}-th argument to '{}' must be a non-negative integer!",
+                position, name
+            ))),
+        },
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a non-negative integer!",
+            position, name
+        ))),
+    }
+}
+
+/// Convert a parsed sexp to the value it denotes, without evaluating it.
+/// Only data can be read: identifiers other than `true` and `false` are
+/// rejected.
+fn read_datum(sexp: &Sexp) -> Result<MankaiObject, RuntimeError> {
+    match sexp {
+        Sexp::List(list) => Ok(MankaiObject::List(
+            list.iter().map(read_datum).collect::<Result<_, _>>()?,
+        )),
+        Sexp::Vector(elements) => Ok(MankaiObject::Vector(Rc::new(
+            elements.iter().map(read_datum).collect::<Result<_, _>>()?,
+        ))),
+        Sexp::Map(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries.iter() {
+                map.insert(read_datum(key)?, read_datum(value)?);
+            }
+
+            Ok(MankaiObject::Map(map))
+        }
+        Sexp::Set(elements) => {
+            let mut set = Set::new();
+            for element in elements.iter() {
+                set.insert(read_datum(element)?);
+            }
+
+            Ok(MankaiObject::Set(set))
+        }
+        Sexp::Atom(token) => match &token.kind {
+            TokenKind::Number(n) => Ok(MankaiObject::Number(n.clone())),
+            TokenKind::String(s) => Ok(MankaiObject::String(s.as_str().into())),
+            TokenKind::Keyword(k) => Ok(MankaiObject::Keyword(k.clone())),
+            TokenKind::Char(c) => Ok(MankaiObject::Char(*c)),
+            TokenKind::Identifier if token.lexeme == "true" => Ok(MankaiObject::Bool(true)),
+            TokenKind::Identifier if token.lexeme == "false" => Ok(MankaiObject::Bool(false)),
+            _ => Err(RuntimeError::new(&format!(
+                "'read-string' can't read '{}'!",
+                token.lexeme
+            ))),
+        },
+    }
+}
 
 // Functions with symbolic names (such as '+', '-', ...).
 
 /// Sum all the arguments. Return an error if a non numeric argument is found
 /// or no arguments are found at all.
-pub fn sum(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn sum(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.is_empty() {
         return Err(RuntimeError::new("'+' requires at least one argument!"));
     }
 
     // Perform the sum.
-    let mut sum = 0.0;
+    let mut sum = Number::from(0);
     for (i, value) in arguments.iter().enumerate() {
         match value {
-            MankaiObject::Number(n) => sum += n,
+            MankaiObject::Number(n) => sum = &sum + n,
             _ => {
                 return Err(RuntimeError::new(&format!(
                     "{}-th argument of '+' must be a number!",
@@ -31,7 +311,10 @@ pub fn sum(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
 /// If only one element is given then substract act just inverts it and return,
 /// if multiple arguments are given multiple substractions are performed
 /// starting from the first argument e.g. substract([a, b, c]) = a - b - c.
-pub fn substract(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn substract(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.is_empty() {
         return Err(RuntimeError::new("'-' requires at least one argument!"));
@@ -47,13 +330,13 @@ pub fn substract(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeEr
 
     // If there are more arguments perform the right number of substractions.
     let mut result = match arguments.get(0).unwrap() {
-        MankaiObject::Number(n) => *n,
+        MankaiObject::Number(n) => n.clone(),
         _ => return Err(RuntimeError::new("1st arguments to '-' must be a number!")),
     };
 
     for (i, value) in arguments.iter().enumerate().skip(1) {
         match value {
-            MankaiObject::Number(n) => result -= n,
+            MankaiObject::Number(n) => result = &result - n,
             _ => {
                 return Err(RuntimeError::new(&format!(
                     "{}-th argument to '-' must be a number!",
@@ -68,17 +351,20 @@ pub fn substract(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeEr
 
 /// Multiply all the arguments. Return an error if a non numeric argument is
 /// found or no arguments are found at all.
-pub fn multiplication(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn multiplication(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.is_empty() {
         return Err(RuntimeError::new("'*' requires at least one argument!"));
     }
 
     // Perform the multiplication of all arguments.
-    let mut result = 1.0;
+    let mut result = Number::from(1);
     for (i, value) in arguments.iter().enumerate() {
         match value {
-            MankaiObject::Number(n) => result *= n,
+            MankaiObject::Number(n) => result = &result * n,
             _ => {
                 return Err(RuntimeError::new(&format!(
                     "{}-th argument to '*' must be a number!",
@@ -94,7 +380,10 @@ pub fn multiplication(arguments: Vec<MankaiObject>) -> Result<MankaiObject, Runt
 /// Divide all the arguments togheter. Return an error if a non numeric
 /// argument is gound or no arguments are found at all.
 /// We impose that division([a]) = 1/a.
-pub fn division(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn division(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.is_empty() {
         return Err(RuntimeError::new("'/' requires at least one argument!"));
@@ -103,29 +392,33 @@ pub fn division(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeErr
     // Handle the one argument case.
     if arguments.len() == 1 {
         return match arguments.get(0).unwrap() {
-            MankaiObject::Number(n) => Ok(MankaiObject::Number(1.0 / n)),
+            MankaiObject::Number(n) => match Number::from(1).checked_div(n) {
+                Some(result) => Ok(MankaiObject::Number(result)),
+                None => Err(RuntimeError::new(
+                    "can't divide by zero (1st argument to '/' is zero)!",
+                )),
+            },
             _ => Err(RuntimeError::new("1st argument to '/' must be a number!")),
         };
     }
 
     // Handle the multiple arguments case.
     let mut result = match arguments.get(0).unwrap() {
-        MankaiObject::Number(n) => *n,
+        MankaiObject::Number(n) => n.clone(),
         _ => return Err(RuntimeError::new("1st argument to '/' must be a number!")),
     };
 
     for (i, value) in arguments.iter().enumerate().skip(1) {
         match value {
-            MankaiObject::Number(n) => {
-                if *n != 0.0 {
-                    result /= n
-                } else {
+            MankaiObject::Number(n) => match result.checked_div(n) {
+                Some(quotient) => result = quotient,
+                None => {
                     return Err(RuntimeError::new(&format!(
                         "can't divide by zero ({}-th argument to '/' is zero)!",
                         i + 1
-                    )));
+                    )))
                 }
-            }
+            },
             _ => {
                 return Err(RuntimeError::new(&format!(
                     "{}-th argument to '/' must be a number!",
@@ -138,33 +431,43 @@ pub fn division(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeErr
     Ok(MankaiObject::Number(result))
 }
 
-/// == implementation.
-pub fn equals(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+/// = implementation: structural equality, except that NaN is not equal to
+/// any number, itself included.
+pub fn equals(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.len() != 2 {
-        return Err(RuntimeError::new("'==' requires exactly two arguments!"));
+        return Err(RuntimeError::new("'=' requires exactly two arguments!"));
     }
 
     let left = arguments.get(0).unwrap();
     let right = arguments.get(1).unwrap();
 
-    Ok(MankaiObject::Bool(left == right))
+    match (left, right) {
+        (MankaiObject::Number(n), MankaiObject::Number(m)) => Ok(MankaiObject::Bool(n == m)),
+        _ => Ok(MankaiObject::Bool(left == right)),
+    }
 }
 
 /// > implementation.
-pub fn greater_than(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn greater_than(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.len() != 2 {
         return Err(RuntimeError::new("'>' reuires exactly two arguments!"));
     }
 
     let left = match arguments.get(0).unwrap() {
-        MankaiObject::Number(n) => *n,
+        MankaiObject::Number(n) => n,
         _ => return Err(RuntimeError::new("1st argument to '>' must be a number!")),
     };
 
     let right = match arguments.get(1).unwrap() {
-        MankaiObject::Number(n) => *n,
+        MankaiObject::Number(n) => n,
         _ => return Err(RuntimeError::new("2nd argument to '>' must be a number!")),
     };
 
@@ -172,19 +475,22 @@ pub fn greater_than(arguments: Vec<MankaiObject>) -> Result<MankaiObject, Runtim
 }
 
 /// < implementation.
-pub fn less_than(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn less_than(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.len() != 2 {
         return Err(RuntimeError::new("'<' reuires exactly two arguments!"));
     }
 
     let left = match arguments.get(0).unwrap() {
-        MankaiObject::Number(n) => *n,
+        MankaiObject::Number(n) => n,
         _ => return Err(RuntimeError::new("1st argument to '<' must be a number!")),
     };
 
     let right = match arguments.get(1).unwrap() {
-        MankaiObject::Number(n) => *n,
+        MankaiObject::Number(n) => n,
         _ => return Err(RuntimeError::new("2nd argument to '<' must be a number!")),
     };
 
@@ -194,7 +500,10 @@ pub fn less_than(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeEr
 // Functions with alfanumeric names.
 
 /// Logic AND with unfixed arity.
-pub fn and(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn and(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.is_empty() {
         return Err(RuntimeError::new("'and' requires at least one argument!"));
@@ -217,8 +526,46 @@ pub fn and(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
     Ok(MankaiObject::Bool(true))
 }
 
-/// Analogue of lisp's iconic `car`: get the head of a list.
-pub fn car(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+/// Check if two values are the same object.
+pub fn eq(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new("'eq?' requires exactly two arguments!"));
+    }
+
+    let left = arguments.first().unwrap();
+    let right = arguments.get(1).unwrap();
+
+    Ok(MankaiObject::Bool(left.is_identical(right)))
+}
+
+/// Check if two values are structurally equal.
+pub fn equal(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'equal?' requires exactly two arguments!",
+        ));
+    }
+
+    let left = arguments.first().unwrap();
+    let right = arguments.get(1).unwrap();
+
+    Ok(MankaiObject::Bool(left == right))
+}
+
+/// Analogue of lisp's iconic `car`: get the head of a list or of a lazy
+/// sequence.
+pub fn car(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.len() != 1 {
         return Err(RuntimeError::new("'car' requires exectly one argument!"));
@@ -229,15 +576,26 @@ pub fn car(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
             if list.is_empty() {
                 Err(RuntimeError::new("can't apply 'car' to the empty list!"))
             } else {
-                Ok(list.get(0).unwrap().clone())
+                Ok(list.first().unwrap().clone())
             }
         }
-        _ => Err(RuntimeError::new("1st argument to 'car' must be a list!")),
+        MankaiObject::LazySeq(sequence) => match sequence.split(interpreter)? {
+            Some((head, _)) => Ok(head),
+            None => Err(RuntimeError::new(
+                "can't apply 'car' to the empty lazy sequence!",
+            )),
+        },
+        _ => Err(RuntimeError::new(
+            "1st argument to 'car' must be a list or a lazy sequence!",
+        )),
     }
 }
 
-/// Analogue of lisp's `cdr`: get the tail of a list.
-pub fn cdr(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+/// Analogue of lisp's `cdr`: get the tail of a list or of a lazy sequence.
+pub fn cdr(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.len() != 1 {
         return Err(RuntimeError::new("'cdr' requires exectly one argument!"));
@@ -248,21 +606,27 @@ pub fn cdr(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
             if list.is_empty() {
                 Err(RuntimeError::new("can't apply 'cdr' to the empty list!"))
             } else {
-                let mut cdr = Vec::new();
-                for value in list.iter().skip(1) {
-                    cdr.push(value.clone());
-                }
-
-                Ok(MankaiObject::List(cdr))
+                Ok(MankaiObject::List(list.rest()))
             }
         }
-        _ => Err(RuntimeError::new("1st argument to 'car' must be a list!")),
+        MankaiObject::LazySeq(sequence) => match sequence.split(interpreter)? {
+            Some((_, tail)) => Ok(MankaiObject::LazySeq(tail)),
+            None => Err(RuntimeError::new(
+                "can't apply 'cdr' to the empty lazy sequence!",
+            )),
+        },
+        _ => Err(RuntimeError::new(
+            "1st argument to 'cdr' must be a list or a lazy sequence!",
+        )),
     }
 }
 
 /// Cons function. Append to the first argument all the others in the given
 /// order. The first argument must be a list.
-pub fn cons(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn cons(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.len() < 2 {
         return Err(RuntimeError::new("'cons' requires at least two arguments!"));
@@ -283,7 +647,10 @@ pub fn cons(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError>
 }
 
 /// Check if the given argument is a Mankai boolean.
-pub fn is_boolean(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn is_boolean(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity. {
     if arguments.len() != 1 {
         return Err(RuntimeError::new("'list?' requires exactly one argument!"));
@@ -295,8 +662,29 @@ pub fn is_boolean(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeE
     }
 }
 
+/// Check if the given argument is a Mankai keyword.
+pub fn is_keyword(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'keyword?' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Keyword(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
 /// Check if the given argument is a Mankai list.
-pub fn is_list(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn is_list(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity. {
     if arguments.len() != 1 {
         return Err(RuntimeError::new("'list?' requires exactly one argument!"));
@@ -309,7 +697,10 @@ pub fn is_list(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeErro
 }
 
 /// Check if the given argument is a Mankai number.
-pub fn is_number(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn is_number(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity. {
     if arguments.len() != 1 {
         return Err(RuntimeError::new(
@@ -324,7 +715,10 @@ pub fn is_number(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeEr
 }
 
 /// Check if the given argument is a Mankai string.
-pub fn is_string(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn is_string(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity. {
     if arguments.len() != 1 {
         return Err(RuntimeError::new(
@@ -339,18 +733,24 @@ pub fn is_string(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeEr
 }
 
 /// Create a new Mankai list from the given Mankai objects.
-pub fn list(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn list(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     let mut list = Vec::new();
 
     for object in arguments {
         list.push(object);
     }
 
-    Ok(MankaiObject::List(list))
+    Ok(MankaiObject::List(list.into()))
 }
 
 /// Logic NOR.
-pub fn not(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn not(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.len() != 1 {
         return Err(RuntimeError::new("'not' requires exactly one argument!"));
@@ -364,7 +764,10 @@ pub fn not(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
 }
 
 /// Logic OR with unfixed arity.
-pub fn or(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn or(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.is_empty() {
         return Err(RuntimeError::new("'or' requires at least one argument!"));
@@ -388,7 +791,10 @@ pub fn or(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
 }
 
 /// Concatenate strings.
-pub fn string_concat(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn string_concat(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.is_empty() {
         return Err(RuntimeError::new(
@@ -410,11 +816,14 @@ pub fn string_concat(arguments: Vec<MankaiObject>) -> Result<MankaiObject, Runti
         }
     }
 
-    Ok(MankaiObject::String(result))
+    Ok(MankaiObject::String(result.into()))
 }
 
 /// Convert a mankai object to a Mankai string.
-pub fn to_string(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+pub fn to_string(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
     // Check arity.
     if arguments.len() != 1 {
         return Err(RuntimeError::new(
@@ -426,6 +835,2671 @@ pub fn to_string(arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeEr
     let value = arguments.get(0).unwrap();
     match value {
         MankaiObject::String(_) => Ok(value.clone()),
-        _ => Ok(MankaiObject::String(value.to_string())),
+        _ => Ok(MankaiObject::String(value.to_string().into())),
+    }
+}
+
+/// Convert a value to the string printed for it, which quotes and escapes
+/// strings too, so that it can be read back by `read-string`.
+pub fn write_to_string(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'write-to-string' requires exactly one argument!",
+        ));
+    }
+
+    Ok(MankaiObject::String(
+        arguments.first().unwrap().to_string().into(),
+    ))
+}
+
+/// Read a value from its printed representation.
+pub fn read_string(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'read-string' requires exactly one argument!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "read-string")?;
+
+    let mut lexer = Lexer::new(String::from(string));
+    if let Err(err) = lexer.scan() {
+        return Err(RuntimeError::new(&format!(
+            "'read-string' can't read the given string: {}!",
+            err.message
+        )));
+    }
+
+    let mut parser = Parser::new(lexer.tokens);
+    match parser.parse() {
+        Ok(sexp) => read_datum(&sexp),
+        Err(err) => Err(RuntimeError::new(&format!(
+            "'read-string' can't read the given string: {}!",
+            err.message
+        ))),
+    }
+}
+
+// Higher-order functions.
+
+/// Check if `predicate` holds for at least one element of a list.
+pub fn any(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new("'any?' requires exactly two arguments!"));
+    }
+
+    let predicate = arguments.first().unwrap();
+    for value in list_argument(&arguments, 2, "any?")? {
+        if call_predicate(interpreter, predicate, value, "any?")? {
+            return Ok(MankaiObject::Bool(true));
+        }
+    }
+
+    Ok(MankaiObject::Bool(false))
+}
+
+/// Call a function with the given arguments, the last one must be a list and
+/// its elements are passed as separate arguments.
+pub fn apply(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() < 2 {
+        return Err(RuntimeError::new(
+            "'apply' requires at least two arguments!",
+        ));
+    }
+
+    // Collect the arguments for the call.
+    let mut call_arguments: Vec<MankaiObject> = arguments
+        .iter()
+        .skip(1)
+        .take(arguments.len() - 2)
+        .cloned()
+        .collect();
+    call_arguments.extend(
+        list_argument(&arguments, arguments.len(), "apply")?
+            .iter()
+            .cloned(),
+    );
+
+    arguments.first().unwrap().call(interpreter, call_arguments)
+}
+
+/// Check if `predicate` holds for all the elements of a list.
+pub fn every(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'every?' requires exactly two arguments!",
+        ));
+    }
+
+    let predicate = arguments.first().unwrap();
+    for value in list_argument(&arguments, 2, "every?")? {
+        if !call_predicate(interpreter, predicate, value, "every?")? {
+            return Ok(MankaiObject::Bool(false));
+        }
+    }
+
+    Ok(MankaiObject::Bool(true))
+}
+
+/// Keep only the elements of a list for which `predicate` holds.
+pub fn filter(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'filter' requires exactly two arguments!",
+        ));
+    }
+
+    let predicate = arguments.first().unwrap();
+    let mut result = Vec::new();
+    for value in list_argument(&arguments, 2, "filter")? {
+        if call_predicate(interpreter, predicate, value, "filter")? {
+            result.push(value.clone());
+        }
+    }
+
+    Ok(MankaiObject::List(result.into()))
+}
+
+/// Return the first element of a list for which `predicate` holds, or the
+/// (optional) third argument if there is none. The default is the empty list.
+pub fn find(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 && arguments.len() != 3 {
+        return Err(RuntimeError::new("'find' requires two or three arguments!"));
+    }
+
+    let predicate = arguments.first().unwrap();
+    for value in list_argument(&arguments, 2, "find")? {
+        if call_predicate(interpreter, predicate, value, "find")? {
+            return Ok(value.clone());
+        }
+    }
+
+    match arguments.get(2) {
+        Some(default) => Ok(default.clone()),
+        None => Ok(MankaiObject::List(List::new())),
+    }
+}
+
+/// Fold a list from the left: `(fold-left f init (list a b))` is
+/// `(f (f init a) b)`.
+pub fn fold_left(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 3 {
+        return Err(RuntimeError::new(
+            "'fold-left' requires exactly three arguments!",
+        ));
+    }
+
+    let function = arguments.first().unwrap();
+    let mut accumulator = arguments.get(1).unwrap().clone();
+    for value in list_argument(&arguments, 3, "fold-left")? {
+        accumulator = function.call(interpreter, vec![accumulator, value.clone()])?;
+    }
+
+    Ok(accumulator)
+}
+
+/// Fold a list from the right: `(fold-right f init (list a b))` is
+/// `(f a (f b init))`.
+pub fn fold_right(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 3 {
+        return Err(RuntimeError::new(
+            "'fold-right' requires exactly three arguments!",
+        ));
+    }
+
+    let function = arguments.first().unwrap();
+    let mut accumulator = arguments.get(1).unwrap().clone();
+    for value in list_argument(&arguments, 3, "fold-right")?.iter().rev() {
+        accumulator = function.call(interpreter, vec![value.clone(), accumulator])?;
+    }
+
+    Ok(accumulator)
+}
+
+/// Apply a function to the elements of one or more lists. With many lists the
+/// function receives one element of each list and the result is as long as
+/// the shortest list.
+pub fn map(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() < 2 {
+        return Err(RuntimeError::new("'map' requires at least two arguments!"));
+    }
+
+    // Get the sequences to map over.
+    let mut sequences = Vec::new();
+    for position in 2..=arguments.len() {
+        sequences.push(sequence_argument(&arguments, position, "map")?);
+    }
+    let length = sequences
+        .iter()
+        .map(|sequence| sequence.len())
+        .min()
+        .unwrap();
+
+    // Perform the mapping.
+    let function = arguments.first().unwrap();
+    let mut result = Vec::new();
+    for i in 0..length {
+        let call_arguments = sequences
+            .iter()
+            .map(|sequence| sequence.get(i).unwrap().clone())
+            .collect();
+        result.push(function.call(interpreter, call_arguments)?);
+    }
+
+    Ok(sequences[0].same_kind(result))
+}
+
+/// Combine the elements of a list with a two-arguments function. The initial
+/// value can be given as second argument, otherwise the first element of the
+/// (non empty) list is used.
+pub fn reduce(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 && arguments.len() != 3 {
+        return Err(RuntimeError::new(
+            "'reduce' requires two or three arguments!",
+        ));
+    }
+
+    let function = arguments.first().unwrap();
+    let list = list_argument(&arguments, arguments.len(), "reduce")?;
+    let mut values = list.iter().cloned();
+
+    // Get the initial value.
+    let mut accumulator = if arguments.len() == 3 {
+        arguments.get(1).unwrap().clone()
+    } else {
+        match values.next() {
+            Some(value) => value,
+            None => {
+                return Err(RuntimeError::new(
+                    "can't apply 'reduce' to the empty list without an initial value!",
+                ))
+            }
+        }
+    };
+
+    for value in values {
+        accumulator = function.call(interpreter, vec![accumulator, value])?;
+    }
+
+    Ok(accumulator)
+}
+
+/// Sort a list by the result of applying a key function to its elements,
+/// ordering the keys like `sort`. The sort is stable.
+pub fn sort_by(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'sort-by' requires exactly two arguments!",
+        ));
+    }
+
+    // Compute the keys.
+    let function = arguments.first().unwrap();
+    let mut keyed = Vec::new();
+    for value in list_argument(&arguments, 2, "sort-by")? {
+        let key = function.call(interpreter, vec![value.clone()])?;
+        keyed.push((key, value.clone()));
+    }
+
+    // Perform the sorting.
+    keyed.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+    Ok(MankaiObject::List(
+        keyed.into_iter().map(|(_, value)| value).collect(),
+    ))
+}
+
+// List functions.
+
+/// Concatenate lists.
+pub fn append(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let mut result = Vec::new();
+    for position in 1..=arguments.len() {
+        result.extend(
+            sequence_argument(&arguments, position, "append")?
+                .iter()
+                .cloned(),
+        );
+    }
+
+    // The result is of the same kind as the first sequence.
+    match arguments.first() {
+        Some(MankaiObject::Vector(_)) => Ok(MankaiObject::Vector(Rc::new(result))),
+        _ => Ok(MankaiObject::List(result.into())),
+    }
+}
+
+/// Drop the first `n` elements of a list.
+pub fn drop(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new("'drop' requires exactly two arguments!"));
+    }
+
+    let sequence = sequence_argument(&arguments, 1, "drop")?;
+    let n = index_argument(&arguments, 2, "drop")?;
+
+    Ok(sequence.same_kind(sequence.iter().skip(n).cloned().collect()))
+}
+
+/// Flatten nested lists into a single list.
+pub fn flatten(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'flatten' requires exactly one argument!",
+        ));
+    }
+
+    fn flatten_into(list: &List, result: &mut Vec<MankaiObject>) {
+        for value in list {
+            match value {
+                MankaiObject::List(inner) => flatten_into(inner, result),
+                _ => result.push(value.clone()),
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    flatten_into(list_argument(&arguments, 1, "flatten")?, &mut result);
+
+    Ok(MankaiObject::List(result.into()))
+}
+
+/// Position of the first occurrence of a value in a list or a vector, -1 if
+/// the value is not there.
+pub fn index_of(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'index-of' requires exactly two arguments!",
+        ));
+    }
+
+    let value = arguments.get(1).unwrap();
+    let index = match sequence_argument(&arguments, 1, "index-of")?.position(value) {
+        Some(index) => index as i64,
+        None => -1,
+    };
+
+    Ok(MankaiObject::Number(Number::from(index)))
+}
+
+/// Last element of a list.
+pub fn last(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'last' requires exactly one argument!"));
+    }
+
+    match sequence_argument(&arguments, 1, "last")?.last() {
+        Some(value) => Ok(value.clone()),
+        None => Err(RuntimeError::new("can't apply 'last' to the empty list!")),
+    }
+}
+
+/// Number of elements of a list.
+pub fn length(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'length' requires exactly one argument!"));
+    }
+
+    let list = sequence_argument(&arguments, 1, "length")?;
+    Ok(MankaiObject::Number(Number::from(list.len() as i64)))
+}
+
+/// Check if a value is an element of a list or a vector.
+pub fn is_member(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'member?' requires exactly two arguments!",
+        ));
+    }
+
+    let sequence = sequence_argument(&arguments, 1, "member?")?;
+    let value = arguments.get(1).unwrap();
+
+    Ok(MankaiObject::Bool(sequence.position(value).is_some()))
+}
+
+/// Element of a list at the given (zero-based) position.
+pub fn nth(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new("'nth' requires exactly two arguments!"));
+    }
+
+    let list = sequence_argument(&arguments, 1, "nth")?;
+    let n = index_argument(&arguments, 2, "nth")?;
+
+    match list.get(n) {
+        Some(value) => Ok(value.clone()),
+        None => Err(RuntimeError::new(&format!(
+            "index {} is out of bounds for a list of length {}!",
+            n,
+            list.len()
+        ))),
+    }
+}
+
+/// List of numbers from `start` (included, 0 if omitted) to `end` (excluded)
+/// separated by `step` (1 if omitted).
+pub fn range(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity and get the bounds.
+    let (start, end, step) = match arguments.len() {
+        1 => (
+            Number::from(0),
+            number_argument(&arguments, 1, "range")?.clone(),
+            Number::from(1),
+        ),
+        2 => (
+            number_argument(&arguments, 1, "range")?.clone(),
+            number_argument(&arguments, 2, "range")?.clone(),
+            Number::from(1),
+        ),
+        3 => (
+            number_argument(&arguments, 1, "range")?.clone(),
+            number_argument(&arguments, 2, "range")?.clone(),
+            number_argument(&arguments, 3, "range")?.clone(),
+        ),
+        _ => {
+            return Err(RuntimeError::new(
+                "'range' requires one, two or three arguments!",
+            ))
+        }
+    };
+
+    if step.is_zero() || !step.to_f64().is_finite() {
+        return Err(RuntimeError::new(
+            "the step of 'range' must be a finite non-zero number!",
+        ));
+    }
+    if [&start, &end]
+        .iter()
+        .any(|bound| !bound.is_exact() && !bound.to_f64().is_finite())
+    {
+        return Err(RuntimeError::new(
+            "the bounds of 'range' must be finite numbers!",
+        ));
+    }
+
+    // Check the length of the list before building it.
+    let length = (&end - &start).checked_div(&step).unwrap().to_f64().ceil();
+    if length > MAX_RANGE_LENGTH as f64 {
+        return Err(RuntimeError::new(&format!(
+            "'range' can't make a list of more than {} elements!",
+            MAX_RANGE_LENGTH
+        )));
+    }
+
+    // Build the list.
+    let mut result = Vec::new();
+    let mut current = start.clone();
+    while (!step.is_negative() && current < end) || (step.is_negative() && current > end) {
+        result.push(MankaiObject::Number(current));
+        current = &start + &(&step * &Number::from(result.len() as i64));
+    }
+
+    Ok(MankaiObject::List(result.into()))
+}
+
+/// Reverse a list.
+pub fn reverse(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'reverse' requires exactly one argument!",
+        ));
+    }
+
+    let sequence = sequence_argument(&arguments, 1, "reverse")?;
+    let mut elements: Vec<_> = sequence.iter().cloned().collect();
+    elements.reverse();
+
+    Ok(sequence.same_kind(elements))
+}
+
+/// Sort a list or a vector in ascending order, using the total order of
+/// values. The sort is stable.
+pub fn sort(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'sort' requires exactly one argument!"));
+    }
+
+    let sequence = sequence_argument(&arguments, 1, "sort")?;
+    let mut elements: Vec<_> = sequence.iter().cloned().collect();
+    elements.sort();
+
+    Ok(sequence.same_kind(elements))
+}
+
+/// Take the first `n` elements of a list or a lazy sequence, as a list.
+pub fn take(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new("'take' requires exactly two arguments!"));
+    }
+
+    let n = index_argument(&arguments, 2, "take")?;
+    if let MankaiObject::LazySeq(sequence) = arguments.first().unwrap() {
+        return Ok(MankaiObject::List(sequence.take(interpreter, n)?));
+    }
+
+    let sequence = sequence_argument(&arguments, 1, "take")?;
+    Ok(sequence.same_kind(sequence.iter().take(n).cloned().collect()))
+}
+
+/// Remove duplicated elements from a list or a vector, keeping the first
+/// occurrence.
+pub fn unique(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'unique' requires exactly one argument!"));
+    }
+
+    let sequence = sequence_argument(&arguments, 1, "unique")?;
+    let mut result: Vec<MankaiObject> = Vec::new();
+    for value in sequence.iter() {
+        if !result.contains(value) {
+            result.push(value.clone());
+        }
+    }
+
+    Ok(sequence.same_kind(result))
+}
+
+/// Group the elements of many lists by position: the result is as long as the
+/// shortest list.
+pub fn zip(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new("'zip' requires at least one argument!"));
+    }
+
+    let mut lists = Vec::new();
+    for position in 1..=arguments.len() {
+        lists.push(list_argument(&arguments, position, "zip")?);
+    }
+    let length = lists.iter().map(|list| list.len()).min().unwrap();
+
+    let mut result = Vec::new();
+    for i in 0..length {
+        result.push(MankaiObject::List(
+            lists.iter().map(|list| list[i].clone()).collect(),
+        ));
+    }
+
+    Ok(MankaiObject::List(result.into()))
+}
+
+// String functions. Lengths and positions are counted in characters (Unicode
+// scalar values), not bytes.
+
+/// Build a list with the characters of a string (as one-character strings).
+pub fn string_to_list(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'string->list' requires exactly one argument!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string->list")?;
+    Ok(MankaiObject::List(
+        string.chars().map(MankaiObject::Char).collect(),
+    ))
+}
+
+/// Concatenate a list of strings.
+pub fn list_to_string(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'list->string' requires exactly one argument!",
+        ));
+    }
+
+    let mut result = String::new();
+    for value in list_argument(&arguments, 1, "list->string")? {
+        match value {
+            MankaiObject::Char(c) => result.push(*c),
+            MankaiObject::String(s) => result.push_str(s),
+            _ => {
+                return Err(RuntimeError::new(
+                    "'list->string' requires a list of characters or strings!",
+                ))
+            }
+        }
+    }
+
+    Ok(MankaiObject::String(result.into()))
+}
+
+/// Check if a string contains another one.
+pub fn string_contains(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'string-contains?' requires exactly two arguments!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-contains?")?;
+    let pattern = string_argument(&arguments, 2, "string-contains?")?;
+
+    Ok(MankaiObject::Bool(string.contains(pattern)))
+}
+
+/// Convert a string to lower case.
+pub fn string_downcase(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'string-downcase' requires exactly one argument!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-downcase")?;
+    Ok(MankaiObject::String(string.to_lowercase().into()))
+}
+
+/// Check if a string ends with another one.
+pub fn string_ends_with(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'string-ends-with?' requires exactly two arguments!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-ends-with?")?;
+    let suffix = string_argument(&arguments, 2, "string-ends-with?")?;
+
+    Ok(MankaiObject::Bool(string.ends_with(suffix)))
+}
+
+/// Position of the first occurrence of a string in another one, -1 if there
+/// is none.
+pub fn string_index(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'string-index' requires exactly two arguments!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-index")?;
+    let pattern = string_argument(&arguments, 2, "string-index")?;
+
+    // Convert the byte offset to a character offset.
+    let index = match string.find(pattern) {
+        Some(offset) => string[..offset].chars().count() as i64,
+        None => -1,
+    };
+
+    Ok(MankaiObject::Number(Number::from(index)))
+}
+
+/// Join a list of strings putting a separator (empty if omitted) between
+/// them.
+pub fn string_join(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 && arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'string-join' requires one or two arguments!",
+        ));
+    }
+
+    let separator = if arguments.len() == 2 {
+        string_argument(&arguments, 2, "string-join")?
+    } else {
+        ""
+    };
+
+    let mut strings = Vec::new();
+    for value in list_argument(&arguments, 1, "string-join")? {
+        match value {
+            MankaiObject::String(s) => strings.push(&**s),
+            _ => {
+                return Err(RuntimeError::new(
+                    "'string-join' requires a list of strings!",
+                ))
+            }
+        }
+    }
+
+    Ok(MankaiObject::String(strings.join(separator).into()))
+}
+
+/// Number of characters of a string.
+pub fn string_length(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'string-length' requires exactly one argument!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-length")?;
+    Ok(MankaiObject::Number(Number::from(
+        string.chars().count() as i64
+    )))
+}
+
+/// Replace all the occurrences of a string with another one.
+pub fn string_replace(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 3 {
+        return Err(RuntimeError::new(
+            "'string-replace' requires exactly three arguments!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-replace")?;
+    let from = string_argument(&arguments, 2, "string-replace")?;
+    let to = string_argument(&arguments, 3, "string-replace")?;
+
+    if from.is_empty() {
+        return Err(RuntimeError::new(
+            "can't replace the empty string in 'string-replace'!",
+        ));
+    }
+
+    Ok(MankaiObject::String(string.replace(from, to).into()))
+}
+
+/// Reverse the characters of a string.
+pub fn string_reverse(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'string-reverse' requires exactly one argument!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-reverse")?;
+    Ok(MankaiObject::String(
+        string.chars().rev().collect::<String>().into(),
+    ))
+}
+
+/// Split a string at each occurrence of a separator, or at whitespace if the
+/// separator is omitted.
+pub fn string_split(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 && arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'string-split' requires one or two arguments!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-split")?;
+    let parts: Vec<&str> = if arguments.len() == 2 {
+        let separator = string_argument(&arguments, 2, "string-split")?;
+        if separator.is_empty() {
+            return Err(RuntimeError::new(
+                "the separator of 'string-split' can't be the empty string!",
+            ));
+        }
+        string.split(separator).collect()
+    } else {
+        string.split_whitespace().collect()
+    };
+
+    Ok(MankaiObject::List(
+        parts
+            .into_iter()
+            .map(|part| MankaiObject::String(part.into()))
+            .collect(),
+    ))
+}
+
+/// Check if a string starts with another one.
+pub fn string_starts_with(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'string-starts-with?' requires exactly two arguments!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-starts-with?")?;
+    let prefix = string_argument(&arguments, 2, "string-starts-with?")?;
+
+    Ok(MankaiObject::Bool(string.starts_with(prefix)))
+}
+
+/// Parse a number out of a string. Return false if the string is not a valid
+/// number.
+pub fn string_to_number(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'string->number' requires exactly one argument!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string->number")?;
+    match Number::parse(string.trim()) {
+        Some(n) => Ok(MankaiObject::Number(n)),
+        None => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+/// Remove leading and trailing whitespace from a string.
+pub fn string_trim(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'string-trim' requires exactly one argument!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-trim")?;
+    Ok(MankaiObject::String(string.trim().into()))
+}
+
+/// Convert a string to upper case.
+pub fn string_upcase(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'string-upcase' requires exactly one argument!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "string-upcase")?;
+    Ok(MankaiObject::String(string.to_uppercase().into()))
+}
+
+/// Characters of a string from position `start` (included) to `end`
+/// (excluded, the end of the string if omitted).
+pub fn substring(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 && arguments.len() != 3 {
+        return Err(RuntimeError::new(
+            "'substring' requires two or three arguments!",
+        ));
+    }
+
+    let string = string_argument(&arguments, 1, "substring")?;
+    let length = string.chars().count();
+    let start = index_argument(&arguments, 2, "substring")?;
+    let end = if arguments.len() == 3 {
+        index_argument(&arguments, 3, "substring")?
+    } else {
+        length
+    };
+
+    if start > end || end > length {
+        return Err(RuntimeError::new(&format!(
+            "invalid range {}-{} for a string of length {} in 'substring'!",
+            start, end, length
+        )));
+    }
+
+    Ok(MankaiObject::String(
+        string
+            .chars()
+            .skip(start)
+            .take(end - start)
+            .collect::<String>()
+            .into(),
+    ))
+}
+
+// Vector functions. Vectors are values: `vector-set` returns a new vector.
+
+/// Get the vector given as `position`-th argument to `name`.
+fn vector_argument<'a>(
+    arguments: &'a [MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<&'a Vec<MankaiObject>, RuntimeError> {
+    match arguments.get(position - 1).unwrap() {
+        MankaiObject::Vector(vector) => Ok(vector.as_ref()),
+        _ => Err(RuntimeError::new(&format!(
+            "{}-th argument to '{}' must be a vector!",
+            position, name
+        ))),
+    }
+}
+
+/// Get the position given as `position`-th argument to the vector function
+/// `name`, checking that it's within the bounds of `vector`.
+fn vector_index(
+    arguments: &[MankaiObject],
+    vector: &[MankaiObject],
+    position: usize,
+    name: &str,
+) -> Result<usize, RuntimeError> {
+    let index = index_argument(arguments, position, name)?;
+
+    if index >= vector.len() {
+        return Err(RuntimeError::new(&format!(
+            "index {} is out of bounds for a vector of length {}!",
+            index,
+            vector.len()
+        )));
+    }
+
+    Ok(index)
+}
+
+/// Make a vector out of the arguments.
+pub fn vector(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    Ok(MankaiObject::Vector(Rc::new(arguments)))
+}
+
+/// Check if the given argument is a vector.
+pub fn is_vector(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'vector?' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Vector(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+/// Element of a vector at the given (zero-based) position.
+pub fn vector_ref(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'vector-ref' requires exactly two arguments!",
+        ));
+    }
+
+    let vector = vector_argument(&arguments, 1, "vector-ref")?;
+    let index = vector_index(&arguments, vector, 2, "vector-ref")?;
+
+    Ok(vector[index].clone())
+}
+
+/// Copy of a vector with the element at the given position replaced.
+pub fn vector_set(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 3 {
+        return Err(RuntimeError::new(
+            "'vector-set' requires exactly three arguments!",
+        ));
+    }
+
+    let vector = vector_argument(&arguments, 1, "vector-set")?;
+    let index = vector_index(&arguments, vector, 2, "vector-set")?;
+
+    let mut result = vector.clone();
+    result[index] = arguments.get(2).unwrap().clone();
+
+    Ok(MankaiObject::Vector(Rc::new(result)))
+}
+
+/// Number of elements of a vector.
+pub fn vector_length(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'vector-length' requires exactly one argument!",
+        ));
+    }
+
+    let vector = vector_argument(&arguments, 1, "vector-length")?;
+    Ok(MankaiObject::Number(Number::from(vector.len() as i64)))
+}
+
+/// List of the elements of a vector.
+pub fn vector_to_list(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'vector->list' requires exactly one argument!",
+        ));
+    }
+
+    let vector = vector_argument(&arguments, 1, "vector->list")?;
+    Ok(MankaiObject::List(vector.iter().cloned().collect()))
+}
+
+/// Vector of the elements of a list.
+pub fn list_to_vector(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'list->vector' requires exactly one argument!",
+        ));
+    }
+
+    let list = list_argument(&arguments, 1, "list->vector")?;
+    Ok(MankaiObject::Vector(Rc::new(list.to_vec())))
+}
+
+// Map functions. Maps are values: the functions "modifying" a map return a
+// new map and leave the given one unchanged.
+
+/// Insert `key value ...` pairs in a map.
+fn insert_pairs(map: &mut Map, pairs: &[MankaiObject], name: &str) -> Result<(), RuntimeError> {
+    if pairs.len() % 2 == 1 {
+        return Err(RuntimeError::new(&format!(
+            "'{}' requires a value for every key!",
+            name
+        )));
+    }
+
+    for pair in pairs.chunks(2) {
+        map.insert(pair[0].clone(), pair[1].clone());
+    }
+
+    Ok(())
+}
+
+/// Make a map out of `key value ...` pairs.
+pub fn hash_map(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let mut map = Map::new();
+    insert_pairs(&mut map, &arguments, "hash-map")?;
+
+    Ok(MankaiObject::Map(map))
+}
+
+/// Check if the given argument is a map.
+pub fn is_map(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'map?' requires exactly one argument!"));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Map(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+/// Value associated to a key in a map. If the key is missing return the
+/// default value if given, the empty list otherwise.
+pub fn get(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 && arguments.len() != 3 {
+        return Err(RuntimeError::new("'get' requires two or three arguments!"));
+    }
+
+    let map = map_argument(&arguments, 1, "get")?;
+    match map.get(arguments.get(1).unwrap()) {
+        Some(value) => Ok(value.clone()),
+        None => match arguments.get(2) {
+            Some(default) => Ok(default.clone()),
+            None => Ok(MankaiObject::List(List::new())),
+        },
+    }
+}
+
+/// Copy of a map with some `key value ...` pairs added.
+pub fn assoc(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() < 3 {
+        return Err(RuntimeError::new(
+            "'assoc' requires at least three arguments!",
+        ));
+    }
+
+    let mut map = map_argument(&arguments, 1, "assoc")?.clone();
+    insert_pairs(&mut map, &arguments[1..], "assoc")?;
+
+    Ok(MankaiObject::Map(map))
+}
+
+/// Copy of a map without some keys.
+pub fn dissoc(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new(
+            "'dissoc' requires at least one argument!",
+        ));
+    }
+
+    let mut map = map_argument(&arguments, 1, "dissoc")?.clone();
+    for key in arguments.iter().skip(1) {
+        map.remove(key);
+    }
+
+    Ok(MankaiObject::Map(map))
+}
+
+/// Check if a key is in a map.
+pub fn contains_key(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'contains-key?' requires exactly two arguments!",
+        ));
+    }
+
+    let map = map_argument(&arguments, 1, "contains-key?")?;
+    Ok(MankaiObject::Bool(
+        map.contains_key(arguments.get(1).unwrap()),
+    ))
+}
+
+/// List of the keys of a map, in insertion order.
+pub fn keys(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'keys' requires exactly one argument!"));
+    }
+
+    let map = map_argument(&arguments, 1, "keys")?;
+    Ok(MankaiObject::List(
+        map.iter().map(|(key, _)| key.clone()).collect(),
+    ))
+}
+
+/// List of the values of a map, in insertion order of their keys.
+pub fn values(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'values' requires exactly one argument!"));
+    }
+
+    let map = map_argument(&arguments, 1, "values")?;
+    Ok(MankaiObject::List(
+        map.iter().map(|(_, value)| value.clone()).collect(),
+    ))
+}
+
+/// Merge some maps. When a key is in many maps the value from the last one
+/// wins.
+pub fn map_merge(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new(
+            "'map-merge' requires at least one argument!",
+        ));
+    }
+
+    let mut result = map_argument(&arguments, 1, "map-merge")?.clone();
+    for position in 2..=arguments.len() {
+        for (key, value) in map_argument(&arguments, position, "map-merge")?.iter() {
+            result.insert(key.clone(), value.clone());
+        }
+    }
+
+    Ok(MankaiObject::Map(result))
+}
+
+// Set functions. Like maps, sets are values: the functions "modifying" a set
+// return a new set and leave the given one unchanged.
+
+/// Make a set out of its elements.
+pub fn set(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let mut set = Set::new();
+    for element in arguments {
+        set.insert(element);
+    }
+
+    Ok(MankaiObject::Set(set))
+}
+
+/// Check if the given argument is a set.
+pub fn is_set(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'set?' requires exactly one argument!"));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Set(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+/// Copy of a set with some elements added.
+pub fn set_add(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new(
+            "'set-add' requires at least one argument!",
+        ));
+    }
+
+    let mut set = set_argument(&arguments, 1, "set-add")?.clone();
+    for element in arguments.iter().skip(1) {
+        set.insert(element.clone());
+    }
+
+    Ok(MankaiObject::Set(set))
+}
+
+/// Copy of a set without some elements.
+pub fn set_remove(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new(
+            "'set-remove' requires at least one argument!",
+        ));
+    }
+
+    let mut set = set_argument(&arguments, 1, "set-remove")?.clone();
+    for element in arguments.iter().skip(1) {
+        set.remove(element);
+    }
+
+    Ok(MankaiObject::Set(set))
+}
+
+/// Check if a value is an element of a set.
+pub fn set_member(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'set-member?' requires exactly two arguments!",
+        ));
+    }
+
+    let set = set_argument(&arguments, 1, "set-member?")?;
+    Ok(MankaiObject::Bool(set.contains(arguments.get(1).unwrap())))
+}
+
+/// Union of some sets, keeping the elements in the order they're first found.
+pub fn union(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let mut result = Set::new();
+    for position in 1..=arguments.len() {
+        for element in set_argument(&arguments, position, "union")?.iter() {
+            result.insert(element.clone());
+        }
+    }
+
+    Ok(MankaiObject::Set(result))
+}
+
+/// Intersection of some sets: the elements of the first set which are in all
+/// the others.
+pub fn intersection(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new(
+            "'intersection' requires at least one argument!",
+        ));
+    }
+
+    let mut result = set_argument(&arguments, 1, "intersection")?.clone();
+    for position in 2..=arguments.len() {
+        let other = set_argument(&arguments, position, "intersection")?;
+        for element in result.clone().iter() {
+            if !other.contains(element) {
+                result.remove(element);
+            }
+        }
+    }
+
+    Ok(MankaiObject::Set(result))
+}
+
+/// Difference of some sets: the elements of the first set which are in none
+/// of the others.
+pub fn difference(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new(
+            "'difference' requires at least one argument!",
+        ));
+    }
+
+    let mut result = set_argument(&arguments, 1, "difference")?.clone();
+    for position in 2..=arguments.len() {
+        for element in set_argument(&arguments, position, "difference")?.iter() {
+            result.remove(element);
+        }
+    }
+
+    Ok(MankaiObject::Set(result))
+}
+
+/// Check if all the elements of a set are in another one.
+pub fn is_subset(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'subset?' requires exactly two arguments!",
+        ));
+    }
+
+    let set = set_argument(&arguments, 1, "subset?")?;
+    let other = set_argument(&arguments, 2, "subset?")?;
+    Ok(MankaiObject::Bool(set.is_subset(other)))
+}
+
+/// List of the elements of a set, in insertion order.
+pub fn set_to_list(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'set->list' requires exactly one argument!",
+        ));
+    }
+
+    let set = set_argument(&arguments, 1, "set->list")?;
+    Ok(MankaiObject::List(set.iter().cloned().collect()))
+}
+
+// Lazy functions. Promises and lazy sequences compute their values when
+// they're first needed and remember them afterwards.
+
+/// Get the value of a promise, computing it if it's the first time it's
+/// forced. Other values are returned as they are.
+pub fn force(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'force' requires exactly one argument!"));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Promise(promise) => promise.force(interpreter),
+        value => Ok(value.clone()),
+    }
+}
+
+/// Make an already forced promise of a value. Promises are returned as they
+/// are.
+pub fn make_promise(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'make-promise' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Promise(promise) => Ok(MankaiObject::Promise(promise.clone())),
+        value => Ok(MankaiObject::Promise(Promise::forced(value.clone()))),
+    }
+}
+
+/// Check if the given argument is a promise.
+pub fn is_promise(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'promise?' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Promise(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+/// Infinite lazy sequence of a value and the results of applying a function
+/// to the previous element.
+pub fn iterate(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'iterate' requires exactly two arguments!",
+        ));
+    }
+
+    let function = arguments.first().unwrap().clone();
+    let value = arguments.get(1).unwrap().clone();
+
+    Ok(MankaiObject::LazySeq(LazySeq::iterate(
+        interpreter,
+        function,
+        value,
+    )))
+}
+
+/// Lazy sequence of the results of applying a function to the elements of
+/// some lists or lazy sequences taken in parallel.
+pub fn lazy_map(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() < 2 {
+        return Err(RuntimeError::new(
+            "'lazy-map' requires at least two arguments!",
+        ));
+    }
+
+    let function = arguments.first().unwrap().clone();
+    let sequences = (2..=arguments.len())
+        .map(|position| lazy_argument(&arguments, position, "lazy-map"))
+        .collect::<Result<_, _>>()?;
+
+    Ok(MankaiObject::LazySeq(LazySeq::map(
+        interpreter,
+        function,
+        sequences,
+    )))
+}
+
+/// Lazy sequence of the elements of a list or lazy sequence for which
+/// `predicate` holds.
+pub fn lazy_filter(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'lazy-filter' requires exactly two arguments!",
+        ));
+    }
+
+    let predicate = arguments.first().unwrap().clone();
+    let sequence = lazy_argument(&arguments, 2, "lazy-filter")?;
+
+    Ok(MankaiObject::LazySeq(LazySeq::filter(
+        interpreter,
+        predicate,
+        sequence,
+    )))
+}
+
+/// Check if the given argument is a lazy sequence.
+pub fn is_lazy_seq(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'lazy-seq?' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::LazySeq(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+// Generator functions.
+
+/// Get the generator given as 1st argument to `name`.
+fn generator_argument(arguments: &[MankaiObject], name: &str) -> Result<Generator, RuntimeError> {
+    match arguments.first().unwrap() {
+        MankaiObject::Generator(generator) => Ok(generator.clone()),
+        _ => Err(RuntimeError::new(&format!(
+            "1st argument to '{}' must be a generator!",
+            name
+        ))),
+    }
+}
+
+/// Make a generator running a function without arguments, suspended each
+/// time it calls `yield`.
+pub fn make_generator(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'make-generator' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        function @ (MankaiObject::Function { .. }
+        | MankaiObject::Native(_)
+        | MankaiObject::SpecialForm(SpecialForm::Yield | SpecialForm::CallCc)
+        | MankaiObject::RecordFunction(_)) => {
+            Ok(MankaiObject::Generator(Generator::new(function.clone())))
+        }
+        _ => Err(RuntimeError::new(
+            "1st argument to 'make-generator' must be a function!",
+        )),
+    }
+}
+
+/// Run a generator until it yields a value or returns, and return that
+/// value. The optional second argument becomes the value of the call to
+/// `yield` which suspended the generator.
+pub fn next(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() || arguments.len() > 2 {
+        return Err(RuntimeError::new("'next' requires one or two arguments!"));
+    }
+
+    let generator = generator_argument(&arguments, "next")?;
+    let value = match arguments.get(1) {
+        Some(value) => value.clone(),
+        None => MankaiObject::List(List::new()),
+    };
+
+    generator.resume(interpreter, value)
+}
+
+/// Check if a generator has finished.
+pub fn generator_done(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'generator-done?' requires exactly one argument!",
+        ));
+    }
+
+    let generator = generator_argument(&arguments, "generator-done?")?;
+    Ok(MankaiObject::Bool(generator.is_done()))
+}
+
+/// Check if the given argument is a generator.
+pub fn is_generator(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'generator?' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Generator(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+/// Suspend the generator running, giving a value to `next`. Calls to `yield`
+/// are handled by the evaluator when they're made by a generator: this
+/// function only runs for the others.
+pub fn yield_value(
+    _interpreter: &mut Interpreter,
+    _arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    Err(RuntimeError::new(
+        "'yield' can only be called by a generator, and not from a function called by a native function or inside 'delay!', 'lazy-cons!', 'let/ec!' or 'parameterize!'!",
+    ))
+}
+
+// Continuation functions.
+
+/// Call a function with the current continuation. The evaluator handles the
+/// calls made by Mankai code, capturing the whole rest of the evaluation:
+/// this function only runs for the calls made by other native functions,
+/// whose continuations can only escape.
+pub fn call_cc(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'call/cc' requires exactly one argument!",
+        ));
+    }
+
+    let escape = Continuation::escape();
+    let result = arguments.first().unwrap().call(
+        interpreter,
+        vec![MankaiObject::Continuation(escape.clone())],
+    );
+
+    escape.catch(result)
+}
+
+// Parameter functions.
+
+/// Make a parameter holding the first argument. The optional second argument
+/// is a function converting the values given to the parameter, starting with
+/// the first one.
+pub fn make_parameter(
+    interpreter: &mut Interpreter,
+    mut arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() || arguments.len() > 2 {
+        return Err(RuntimeError::new(
+            "'make-parameter' requires one or two arguments!",
+        ));
+    }
+
+    let converter = match arguments.get(1) {
+        None => None,
+        Some(
+            function @ (MankaiObject::Function { .. }
+            | MankaiObject::Native(_)
+            | MankaiObject::SpecialForm(SpecialForm::Yield | SpecialForm::CallCc)
+            | MankaiObject::RecordFunction(_)),
+        ) => Some(function.clone()),
+        Some(_) => {
+            return Err(RuntimeError::new(
+                "2nd argument to 'make-parameter' must be a function!",
+            ))
+        }
+    };
+
+    let value = arguments.swap_remove(0);
+    Ok(MankaiObject::Parameter(Parameter::new(
+        interpreter,
+        value,
+        converter,
+    )?))
+}
+
+/// Check if the given argument is a parameter.
+pub fn is_parameter(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'parameter?' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Parameter(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+// Box functions.
+
+/// Get the box given as 1st argument to `name`.
+fn box_argument(arguments: &[MankaiObject], name: &str) -> Result<Reference, RuntimeError> {
+    match arguments.first().unwrap() {
+        MankaiObject::Box(reference) => Ok(reference.clone()),
+        _ => Err(RuntimeError::new(&format!(
+            "1st argument to '{}' must be a box!",
+            name
+        ))),
+    }
+}
+
+/// Make a box holding the given value.
+pub fn make_box(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'box' requires exactly one argument!"));
+    }
+
+    Ok(MankaiObject::Box(Reference::new(
+        arguments.first().unwrap().clone(),
+    )))
+}
+
+/// Get the value held by a box.
+pub fn unbox(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'unbox' requires exactly one argument!"));
+    }
+
+    Ok(box_argument(&arguments, "unbox")?.get())
+}
+
+/// Change the value held by a box, and return the new value.
+pub fn set_box(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'set-box!' requires exactly two arguments!",
+        ));
+    }
+
+    let value = arguments.get(1).unwrap().clone();
+    box_argument(&arguments, "set-box!")?.set(value.clone());
+
+    Ok(value)
+}
+
+/// Check if the given argument is a box.
+pub fn is_box(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'box?' requires exactly one argument!"));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Box(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+// Atom functions.
+
+/// Get the atom given as 1st argument to `name`.
+fn atom_argument(arguments: &[MankaiObject], name: &str) -> Result<Reference, RuntimeError> {
+    match arguments.first().unwrap() {
+        MankaiObject::Atom(reference) => Ok(reference.clone()),
+        _ => Err(RuntimeError::new(&format!(
+            "1st argument to '{}' must be an atom!",
+            name
+        ))),
+    }
+}
+
+/// Make an atom holding the given value.
+pub fn atom(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'atom' requires exactly one argument!"));
+    }
+
+    Ok(MankaiObject::Atom(Reference::new(
+        arguments.first().unwrap().clone(),
+    )))
+}
+
+/// Get the value held by an atom.
+pub fn deref(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'deref' requires exactly one argument!"));
+    }
+
+    Ok(atom_argument(&arguments, "deref")?.get())
+}
+
+/// Replace the value of an atom by the result of calling a function with it
+/// (and with the other arguments), and return the new value. If the function
+/// changes the atom itself, the new value is dropped and the function is
+/// called again with the value it put there, until the atom is left alone or
+/// `MAX_SWAP_ATTEMPTS` calls have been made.
+pub fn swap(
+    interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() < 2 {
+        return Err(RuntimeError::new(
+            "'swap!' requires at least two arguments!",
+        ));
+    }
+
+    let reference = atom_argument(&arguments, "swap!")?;
+    let function = arguments.get(1).unwrap();
+    for _ in 0..MAX_SWAP_ATTEMPTS {
+        let old = reference.get();
+        let mut function_arguments = vec![old.clone()];
+        function_arguments.extend_from_slice(&arguments[2..]);
+        let new = function.call(interpreter, function_arguments)?;
+
+        // Only store the new value if the atom still holds the old one.
+        if reference.get().is_identical(&old) {
+            reference.set(new.clone());
+            return Ok(new);
+        }
+    }
+
+    Err(RuntimeError::new(&format!(
+        "'swap!' gave up after {} attempts, since the function kept changing the atom!",
+        MAX_SWAP_ATTEMPTS
+    )))
+}
+
+/// Change the value of an atom, and return the new value.
+pub fn reset(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'reset!' requires exactly two arguments!",
+        ));
+    }
+
+    let value = arguments.get(1).unwrap().clone();
+    atom_argument(&arguments, "reset!")?.set(value.clone());
+
+    Ok(value)
+}
+
+/// Check if the given argument is an atom.
+pub fn is_atom(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'atom?' requires exactly one argument!"));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Atom(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+// Character functions.
+
+/// Get the only (character) argument of the character function `name`.
+fn single_char(arguments: &[MankaiObject], name: &str) -> Result<char, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(&format!(
+            "'{}' requires exactly one argument!",
+            name
+        )));
+    }
+
+    char_argument(arguments, 1, name)
+}
+
+/// Check if the given argument is a character.
+pub fn is_char(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'char?' requires exactly one argument!"));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Char(_) => Ok(MankaiObject::Bool(true)),
+        _ => Ok(MankaiObject::Bool(false)),
+    }
+}
+
+/// Check if a character is alphabetic.
+pub fn is_char_alphabetic(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char-alphabetic?")?;
+    Ok(MankaiObject::Bool(c.is_alphabetic()))
+}
+
+/// Check if a character is numeric.
+pub fn is_char_numeric(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char-numeric?")?;
+    Ok(MankaiObject::Bool(c.is_numeric()))
+}
+
+/// Check if a character is whitespace.
+pub fn is_char_whitespace(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char-whitespace?")?;
+    Ok(MankaiObject::Bool(c.is_whitespace()))
+}
+
+/// Check if a character is an uppercase letter.
+pub fn is_char_upper_case(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char-upper-case?")?;
+    Ok(MankaiObject::Bool(c.is_uppercase()))
+}
+
+/// Check if a character is a lowercase letter.
+pub fn is_char_lower_case(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char-lower-case?")?;
+    Ok(MankaiObject::Bool(c.is_lowercase()))
+}
+
+/// Uppercase version of a character. Characters whose uppercase version is
+/// made of many characters are returned unchanged.
+pub fn char_upcase(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char-upcase")?;
+
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(upper), None) => Ok(MankaiObject::Char(upper)),
+        _ => Ok(MankaiObject::Char(c)),
+    }
+}
+
+/// Lowercase version of a character. Characters whose lowercase version is
+/// made of many characters are returned unchanged.
+pub fn char_downcase(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char-downcase")?;
+
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => Ok(MankaiObject::Char(lower)),
+        _ => Ok(MankaiObject::Char(c)),
+    }
+}
+
+/// Unicode code point of a character.
+pub fn char_to_integer(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char->integer")?;
+    Ok(MankaiObject::Number(Number::from(c as i64)))
+}
+
+/// Character with the given Unicode code point.
+pub fn integer_to_char(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'integer->char' requires exactly one argument!",
+        ));
+    }
+
+    let code = index_argument(&arguments, 1, "integer->char")?;
+    match std::char::from_u32(code as u32).filter(|_| code <= u32::MAX as usize) {
+        Some(c) => Ok(MankaiObject::Char(c)),
+        None => Err(RuntimeError::new(&format!(
+            "{} is not a valid character code!",
+            code
+        ))),
+    }
+}
+
+/// Convert a character to a one-character string.
+pub fn char_to_string(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let c = single_char(&arguments, "char->string")?;
+    Ok(MankaiObject::String(c.to_string().into()))
+}
+
+// Math functions.
+
+/// Get the only (numeric) argument of the math function `name`.
+fn single_number<'a>(
+    arguments: &'a [MankaiObject],
+    name: &str,
+) -> Result<&'a Number, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(&format!(
+            "'{}' requires exactly one argument!",
+            name
+        )));
+    }
+
+    number_argument(arguments, 1, name)
+}
+
+/// Get the two (numeric) arguments of the math function `name`.
+fn two_numbers<'a>(
+    arguments: &'a [MankaiObject],
+    name: &str,
+) -> Result<(&'a Number, &'a Number), RuntimeError> {
+    // Check arity.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(&format!(
+            "'{}' requires exactly two arguments!",
+            name
+        )));
+    }
+
+    Ok((
+        number_argument(arguments, 1, name)?,
+        number_argument(arguments, 2, name)?,
+    ))
+}
+
+/// Wrap the (inexact) result of the math function `name`, reporting an error
+/// instead of returning NaN.
+fn math_result(value: f64, name: &str) -> Result<MankaiObject, RuntimeError> {
+    if value.is_nan() {
+        Err(RuntimeError::new(&format!(
+            "'{}' is not defined for the given arguments!",
+            name
+        )))
+    } else {
+        Ok(MankaiObject::Number(Number::Float(value)))
+    }
+}
+
+/// Get the two arguments of an integer division-like function `name`, checking
+/// that the divisor is not zero.
+fn division_operands<'a>(
+    arguments: &'a [MankaiObject],
+    name: &str,
+) -> Result<(&'a Number, &'a Number), RuntimeError> {
+    let (dividend, divisor) = two_numbers(arguments, name)?;
+
+    if divisor.is_zero() {
+        return Err(RuntimeError::new(&format!(
+            "can't divide by zero (2nd argument to '{}' is zero)!",
+            name
+        )));
+    }
+
+    Ok((dividend, divisor))
+}
+
+/// Exact square root of a non-negative machine integer, if it is a perfect
+/// square.
+fn exact_sqrt(n: i64) -> Option<i64> {
+    let root = (n as f64).sqrt().round() as i64;
+
+    // Correct the rounding errors of the float square root.
+    (root - 1..=root + 1).find(|r| *r >= 0 && r.checked_mul(*r) == Some(n))
+}
+
+/// Absolute value.
+pub fn abs(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "abs")?;
+    Ok(MankaiObject::Number(n.abs()))
+}
+
+/// Arc cosine.
+pub fn acos(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "acos")?.to_f64();
+
+    if !(-1.0..=1.0).contains(&n) {
+        return Err(RuntimeError::new(
+            "argument to 'acos' must be between -1 and 1!",
+        ));
+    }
+
+    math_result(n.acos(), "acos")
+}
+
+/// Arc sine.
+pub fn asin(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "asin")?.to_f64();
+
+    if !(-1.0..=1.0).contains(&n) {
+        return Err(RuntimeError::new(
+            "argument to 'asin' must be between -1 and 1!",
+        ));
+    }
+
+    math_result(n.asin(), "asin")
+}
+
+/// Arc tangent.
+pub fn atan(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "atan")?.to_f64();
+    math_result(n.atan(), "atan")
+}
+
+/// Arc tangent of `y / x` using the signs of the arguments to pick the
+/// quadrant.
+pub fn atan2(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let (y, x) = two_numbers(&arguments, "atan2")?;
+    math_result(y.to_f64().atan2(x.to_f64()), "atan2")
+}
+
+/// Smallest integer greater than or equal to the argument.
+pub fn ceil(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "ceil")?;
+    Ok(MankaiObject::Number(n.round(Rounding::Ceil)))
+}
+
+/// Cosine.
+pub fn cos(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "cos")?.to_f64();
+    math_result(n.cos(), "cos")
+}
+
+/// Exponential function.
+pub fn exp(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "exp")?.to_f64();
+    math_result(n.exp(), "exp")
+}
+
+/// Greatest integer less than or equal to the argument.
+pub fn floor(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "floor")?;
+    Ok(MankaiObject::Number(n.round(Rounding::Floor)))
+}
+
+/// Logarithm, natural unless a base is given as second argument.
+pub fn log(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 && arguments.len() != 2 {
+        return Err(RuntimeError::new("'log' requires one or two arguments!"));
+    }
+
+    let n = number_argument(&arguments, 1, "log")?.to_f64();
+    if n <= 0.0 {
+        return Err(RuntimeError::new("1st argument to 'log' must be positive!"));
+    }
+
+    if arguments.len() == 2 {
+        let base = number_argument(&arguments, 2, "log")?.to_f64();
+        if base <= 0.0 || base == 1.0 {
+            return Err(RuntimeError::new(
+                "2nd argument to 'log' must be positive and different from 1!",
+            ));
+        }
+
+        math_result(n.log(base), "log")
+    } else {
+        math_result(n.ln(), "log")
+    }
+}
+
+/// Maximum of the arguments. The result is inexact if any argument is.
+pub fn max(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new("'max' requires at least one argument!"));
+    }
+
+    let mut result = number_argument(&arguments, 1, "max")?;
+    let mut exact = result.is_exact();
+    for position in 2..=arguments.len() {
+        let n = number_argument(&arguments, position, "max")?;
+        exact = exact && n.is_exact();
+        if n > result {
+            result = n;
+        }
+    }
+
+    if exact {
+        Ok(MankaiObject::Number(result.clone()))
+    } else {
+        math_result(result.to_f64(), "max")
+    }
+}
+
+/// Minimum of the arguments. The result is inexact if any argument is.
+pub fn min(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new("'min' requires at least one argument!"));
+    }
+
+    let mut result = number_argument(&arguments, 1, "min")?;
+    let mut exact = result.is_exact();
+    for position in 2..=arguments.len() {
+        let n = number_argument(&arguments, position, "min")?;
+        exact = exact && n.is_exact();
+        if n < result {
+            result = n;
+        }
+    }
+
+    if exact {
+        Ok(MankaiObject::Number(result.clone()))
+    } else {
+        math_result(result.to_f64(), "min")
+    }
+}
+
+/// Modulo: the remainder of the floored division, it has the same sign as the
+/// divisor.
+pub fn modulo(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let (dividend, divisor) = division_operands(&arguments, "mod")?;
+
+    match dividend.div_rem(divisor) {
+        Some((_, remainder)) => {
+            if !remainder.is_zero() && remainder.is_negative() != divisor.is_negative() {
+                Ok(MankaiObject::Number(&remainder + divisor))
+            } else {
+                Ok(MankaiObject::Number(remainder))
+            }
+        }
+        None => {
+            let (dividend, divisor) = (dividend.to_f64(), divisor.to_f64());
+            math_result(dividend - divisor * (dividend / divisor).floor(), "mod")
+        }
+    }
+}
+
+/// Raise a number to a power. The result is exact if the base is exact and
+/// the exponent is an exact integer.
+pub fn pow(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let (base, exponent) = two_numbers(&arguments, "pow")?;
+
+    if base.is_zero() && exponent.is_negative() {
+        return Err(RuntimeError::new(
+            "can't raise zero to a negative power in 'pow'!",
+        ));
+    }
+
+    if let (true, Some(exponent)) = (base.is_exact(), exponent.to_i64()) {
+        return match base.exact_pow(exponent) {
+            Some(result) => Ok(MankaiObject::Number(result)),
+            None => Err(RuntimeError::new("the result of 'pow' is too big!")),
+        };
+    }
+
+    let (base, exponent) = (base.to_f64(), exponent.to_f64());
+    if base < 0.0 && exponent.fract() != 0.0 {
+        return Err(RuntimeError::new(
+            "can't raise a negative number to a non-integer power in 'pow'!",
+        ));
+    }
+
+    math_result(base.powf(exponent), "pow")
+}
+
+/// Quotient of the division truncated towards zero.
+pub fn quotient(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let (dividend, divisor) = division_operands(&arguments, "quotient")?;
+
+    match dividend.div_rem(divisor) {
+        Some((quotient, _)) => Ok(MankaiObject::Number(quotient)),
+        None => math_result((dividend.to_f64() / divisor.to_f64()).trunc(), "quotient"),
+    }
+}
+
+/// Remainder of the division truncated towards zero, it has the same sign as
+/// the dividend.
+pub fn remainder(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let (dividend, divisor) = division_operands(&arguments, "remainder")?;
+
+    match dividend.div_rem(divisor) {
+        Some((_, remainder)) => Ok(MankaiObject::Number(remainder)),
+        None => math_result(dividend.to_f64() % divisor.to_f64(), "remainder"),
+    }
+}
+
+/// Round to the nearest integer, halfway cases are rounded away from zero.
+pub fn round(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "round")?;
+    Ok(MankaiObject::Number(n.round(Rounding::Round)))
+}
+
+/// Sine.
+pub fn sin(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "sin")?.to_f64();
+    math_result(n.sin(), "sin")
+}
+
+/// Square root. The square root of an exact perfect square is exact.
+pub fn sqrt(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "sqrt")?;
+
+    if n.is_negative() {
+        return Err(RuntimeError::new(
+            "can't take the square root of a negative number!",
+        ));
+    }
+
+    match n {
+        Number::Fixnum(m) => {
+            if let Some(root) = exact_sqrt(*m) {
+                return Ok(MankaiObject::Number(Number::from(root)));
+            }
+        }
+        Number::Rational(numerator, denominator) => {
+            if let (Some(numerator), Some(denominator)) = (
+                numerator.to_i64().and_then(exact_sqrt),
+                denominator.to_i64().and_then(exact_sqrt),
+            ) {
+                return Ok(MankaiObject::Number(Number::from_ratio(
+                    BigInt::from_i64(numerator),
+                    BigInt::from_i64(denominator),
+                )));
+            }
+        }
+        _ => (),
+    }
+
+    math_result(n.to_f64().sqrt(), "sqrt")
+}
+
+/// Tangent.
+pub fn tan(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "tan")?.to_f64();
+    math_result(n.tan(), "tan")
+}
+
+/// Integer part of a number (rounding towards zero).
+pub fn truncate(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "truncate")?;
+    Ok(MankaiObject::Number(n.round(Rounding::Truncate)))
+}
+
+// Numeric tower functions.
+
+/// Convert a number to an exact one.
+pub fn exact(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "exact")?;
+
+    match n.to_exact() {
+        Some(n) => Ok(MankaiObject::Number(n)),
+        None => Err(RuntimeError::new(&format!(
+            "can't convert {} to an exact number!",
+            n
+        ))),
+    }
+}
+
+/// Convert a number to an inexact one.
+pub fn inexact(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "inexact")?;
+    Ok(MankaiObject::Number(n.to_inexact()))
+}
+
+/// Check if the given argument is an exact number.
+pub fn is_exact(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "exact?")?;
+    Ok(MankaiObject::Bool(n.is_exact()))
+}
+
+/// Check if the given argument is an inexact number.
+pub fn is_inexact(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    let n = single_number(&arguments, "inexact?")?;
+    Ok(MankaiObject::Bool(!n.is_exact()))
+}
+
+/// Check if the given argument is an integer (exact or not).
+pub fn is_integer(
+    _interpreter: &mut Interpreter,
+    arguments: Vec<MankaiObject>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Check arity.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new(
+            "'integer?' requires exactly one argument!",
+        ));
+    }
+
+    match arguments.first().unwrap() {
+        MankaiObject::Number(n) => Ok(MankaiObject::Bool(n.is_integer())),
+        _ => Ok(MankaiObject::Bool(false)),
     }
 }
@@ -0,0 +1,570 @@
+use std::cmp::Ordering;
+
+use crate::bigint::BigInt;
+
+/// A Mankai number. Arithmetic keeps exact numbers (integers and rationals)
+/// exact, while floats are contagious: if any operand is a float the result
+/// is a float.
+#[derive(Debug, Clone)]
+pub enum Number {
+    /// An exact integer fitting in 64 bits.
+    Fixnum(i64),
+    /// An exact integer not fitting in 64 bits.
+    Bignum(BigInt),
+    /// An exact non-integer fraction, always in lowest terms and with a
+    /// denominator greater than one.
+    Rational(BigInt, BigInt),
+    /// An inexact number.
+    Float(f64),
+}
+
+/// Ways of rounding a number to an integer.
+#[derive(Clone, Copy)]
+pub enum Rounding {
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceil,
+    /// To the nearest integer, halfway cases away from zero.
+    Round,
+    /// Towards zero.
+    Truncate,
+}
+
+impl Number {
+    /// Make an exact integer out of a big integer, using a fixnum if it fits.
+    pub fn from_bigint(n: BigInt) -> Self {
+        match n.to_i64() {
+            Some(n) => Number::Fixnum(n),
+            None => Number::Bignum(n),
+        }
+    }
+
+    /// Make an exact fraction, reducing it to lowest terms. The denominator
+    /// must not be zero.
+    pub fn from_ratio(numerator: BigInt, denominator: BigInt) -> Self {
+        let (numerator, denominator) = if denominator.is_negative() {
+            (-&numerator, -&denominator)
+        } else {
+            (numerator, denominator)
+        };
+
+        let gcd = numerator.gcd(&denominator);
+        let (numerator, _) = numerator.div_rem(&gcd);
+        let (denominator, _) = denominator.div_rem(&gcd);
+
+        if denominator == BigInt::from_i64(1) {
+            Number::from_bigint(numerator)
+        } else {
+            Number::Rational(numerator, denominator)
+        }
+    }
+
+    /// Parse a number literal. Accepted forms, all with an optional leading
+    /// sign, are integers (`12`, `1_000`), integers in other radixes (`0xff`,
+    /// `0o17`, `0b1010`), fractions (`1/2`), decimals (`1.5`, `2.5e-3`, `1e10`)
+    /// and the special floats `inf` and `nan`.
+    pub fn parse(string: &str) -> Option<Self> {
+        let (negative, unsigned) = match string.chars().next() {
+            Some('-') => (true, &string[1..]),
+            Some('+') => (false, &string[1..]),
+            _ => (false, string),
+        };
+
+        let number = Number::parse_unsigned(unsigned)?;
+        if negative {
+            Some(-&number)
+        } else {
+            Some(number)
+        }
+    }
+
+    /// Parse a number literal without sign.
+    fn parse_unsigned(string: &str) -> Option<Self> {
+        match string {
+            "inf" => return Some(Number::Float(f64::INFINITY)),
+            "nan" => return Some(Number::Float(f64::NAN)),
+            _ => (),
+        }
+
+        // Integers in other radixes.
+        let radix = match string.get(..2) {
+            Some("0x") | Some("0X") => Some(16),
+            Some("0o") | Some("0O") => Some(8),
+            Some("0b") | Some("0B") => Some(2),
+            _ => None,
+        };
+        if let Some(radix) = radix {
+            let digits = remove_separators(&string[2..], radix)?;
+            return Some(Number::from_bigint(BigInt::parse(&digits, radix)?));
+        }
+
+        // Fractions.
+        if let Some(slash) = string.find('/') {
+            let numerator = remove_separators(&string[..slash], 10)?;
+            let denominator = remove_separators(&string[slash + 1..], 10)?;
+            let denominator = BigInt::parse(&denominator, 10)?;
+            if denominator.is_zero() {
+                return None;
+            }
+
+            return Some(Number::from_ratio(
+                BigInt::parse(&numerator, 10)?,
+                denominator,
+            ));
+        }
+
+        // Split the decimal literal in integer part, fractional part and
+        // exponent.
+        let (mantissa, exponent) = match string.find(&['e', 'E'][..]) {
+            Some(e) => (&string[..e], Some(&string[e + 1..])),
+            None => (string, None),
+        };
+        let (integer, fraction) = match mantissa.find('.') {
+            Some(dot) => (&mantissa[..dot], Some(&mantissa[dot + 1..])),
+            None => (mantissa, None),
+        };
+
+        let integer = remove_separators(integer, 10)?;
+        if fraction.is_none() && exponent.is_none() {
+            return Some(Number::from_bigint(BigInt::parse(&integer, 10)?));
+        }
+
+        let mut literal = integer;
+        if let Some(fraction) = fraction {
+            literal.push('.');
+            literal.push_str(&remove_separators(fraction, 10)?);
+        }
+        if let Some(exponent) = exponent {
+            let (sign, digits) = match exponent.chars().next() {
+                Some(sign) if sign == '-' || sign == '+' => (&exponent[..1], &exponent[1..]),
+                _ => ("", exponent),
+            };
+            literal.push('e');
+            literal.push_str(sign);
+            literal.push_str(&remove_separators(digits, 10)?);
+        }
+
+        literal.parse::<f64>().ok().map(Number::Float)
+    }
+
+    /// Exact value as a (numerator, denominator) pair, `None` for floats.
+    fn to_ratio(&self) -> Option<(BigInt, BigInt)> {
+        match self {
+            Number::Fixnum(n) => Some((BigInt::from_i64(*n), BigInt::from_i64(1))),
+            Number::Bignum(n) => Some((n.clone(), BigInt::from_i64(1))),
+            Number::Rational(n, d) => Some((n.clone(), d.clone())),
+            Number::Float(_) => None,
+        }
+    }
+
+    /// Convert to the closest float.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Fixnum(n) => *n as f64,
+            Number::Bignum(n) => n.to_f64(),
+            Number::Rational(n, d) => n.to_f64() / d.to_f64(),
+            Number::Float(n) => *n,
+        }
+    }
+
+    /// Convert to a machine integer if the number is an exact integer that
+    /// fits in it.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            Number::Fixnum(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Convert to an exact number. Floats are converted to the fraction they
+    /// represent exactly, infinities and NaN can't be converted.
+    pub fn to_exact(&self) -> Option<Number> {
+        match self {
+            Number::Float(n) => {
+                if !n.is_finite() {
+                    return None;
+                }
+
+                // Decompose the float as mantissa * 2^exponent.
+                let bits = n.to_bits();
+                let biased_exponent = ((bits >> 52) & 0x7ff) as i64;
+                let fraction = (bits & 0x000f_ffff_ffff_ffff) as i64;
+                let (mantissa, exponent) = if biased_exponent == 0 {
+                    (fraction, -1074)
+                } else {
+                    (fraction | 0x0010_0000_0000_0000, biased_exponent - 1075)
+                };
+                let mantissa = if *n < 0.0 { -mantissa } else { mantissa };
+
+                let mut power = BigInt::from_i64(1);
+                let two = BigInt::from_i64(2);
+                for _ in 0..exponent.abs() {
+                    power = &power * &two;
+                }
+
+                if exponent >= 0 {
+                    Some(Number::from_bigint(&BigInt::from_i64(mantissa) * &power))
+                } else {
+                    Some(Number::from_ratio(BigInt::from_i64(mantissa), power))
+                }
+            }
+            _ => Some(self.clone()),
+        }
+    }
+
+    /// Convert to a float.
+    pub fn to_inexact(&self) -> Number {
+        Number::Float(self.to_f64())
+    }
+
+    /// Check if the number is exact.
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, Number::Float(_))
+    }
+
+    /// Check if the number is an integer (exact or not).
+    pub fn is_integer(&self) -> bool {
+        match self {
+            Number::Fixnum(_) | Number::Bignum(_) => true,
+            Number::Rational(_, _) => false,
+            Number::Float(n) => n.is_finite() && n.fract() == 0.0,
+        }
+    }
+
+    /// Check if the number is a float NaN.
+    pub fn is_nan(&self) -> bool {
+        matches!(self, Number::Float(n) if n.is_nan())
+    }
+
+    /// Check if the number is zero.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Fixnum(n) => *n == 0,
+            Number::Float(n) => *n == 0.0,
+            _ => false,
+        }
+    }
+
+    /// Check if the number is negative.
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Number::Fixnum(n) => *n < 0,
+            Number::Bignum(n) | Number::Rational(n, _) => n.is_negative(),
+            Number::Float(n) => *n < 0.0,
+        }
+    }
+
+    /// Apply an operation, using `fixnum` if both numbers are fixnums (and it
+    /// does not overflow), `exact` if both are exact and `float` otherwise.
+    fn arithmetic(
+        &self,
+        other: &Number,
+        fixnum: fn(i64, i64) -> Option<i64>,
+        exact: fn((BigInt, BigInt), (BigInt, BigInt)) -> Number,
+        float: fn(f64, f64) -> f64,
+    ) -> Number {
+        if let (Number::Fixnum(a), Number::Fixnum(b)) = (self, other) {
+            if let Some(result) = fixnum(*a, *b) {
+                return Number::Fixnum(result);
+            }
+        }
+
+        match (self.to_ratio(), other.to_ratio()) {
+            (Some(a), Some(b)) => exact(a, b),
+            _ => Number::Float(float(self.to_f64(), other.to_f64())),
+        }
+    }
+
+    /// Division, `None` if `other` is zero.
+    pub fn checked_div(&self, other: &Number) -> Option<Number> {
+        if other.is_zero() {
+            return None;
+        }
+
+        Some(self.arithmetic(
+            other,
+            |a, b| match a.checked_rem(b) {
+                Some(0) => a.checked_div(b),
+                _ => None,
+            },
+            |(a, b), (c, d)| Number::from_ratio(&a * &d, &b * &c),
+            |a, b| a / b,
+        ))
+    }
+
+    /// Quotient and remainder of the division truncated towards zero, `None`
+    /// if `other` is zero or if the numbers are not both exact integers.
+    pub fn div_rem(&self, other: &Number) -> Option<(Number, Number)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        match (self, other) {
+            (Number::Fixnum(a), Number::Fixnum(b)) if !(*a == i64::MIN && *b == -1) => {
+                Some((Number::Fixnum(a / b), Number::Fixnum(a % b)))
+            }
+            (Number::Fixnum(_), _) | (Number::Bignum(_), _) if other.is_exact() => {
+                let (a, _) = self.to_ratio()?;
+                let (b, one) = other.to_ratio()?;
+                if one != BigInt::from_i64(1) {
+                    return None;
+                }
+
+                let (quotient, remainder) = a.div_rem(&b);
+                Some((
+                    Number::from_bigint(quotient),
+                    Number::from_bigint(remainder),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Round to an integer. Exact numbers give exact integers, floats give
+    /// floats.
+    pub fn round(&self, rounding: Rounding) -> Number {
+        match self {
+            Number::Float(n) => Number::Float(match rounding {
+                Rounding::Floor => n.floor(),
+                Rounding::Ceil => n.ceil(),
+                Rounding::Round => n.round(),
+                Rounding::Truncate => n.trunc(),
+            }),
+            Number::Rational(n, d) => {
+                let (quotient, remainder) = n.div_rem(d);
+                let one = BigInt::from_i64(1);
+                let away_from_zero = if n.is_negative() {
+                    &quotient - &one
+                } else {
+                    &quotient + &one
+                };
+
+                let result = match rounding {
+                    Rounding::Floor if n.is_negative() => away_from_zero,
+                    Rounding::Ceil if !n.is_negative() => away_from_zero,
+                    Rounding::Round if &remainder.abs() * &BigInt::from_i64(2) >= *d => {
+                        away_from_zero
+                    }
+                    _ => quotient,
+                };
+
+                Number::from_bigint(result)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Absolute value.
+    pub fn abs(&self) -> Number {
+        if self.is_negative() {
+            -self
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Raise to an integer power, `None` if the result can't be computed
+    /// exactly (the base is zero and the exponent is negative or the result
+    /// would be too big).
+    pub fn exact_pow(&self, exponent: i64) -> Option<Number> {
+        let (numerator, denominator) = self.to_ratio()?;
+
+        // Refuse to build absurdly big numbers.
+        let bits = (numerator.bits().max(denominator.bits()) as u64)
+            .saturating_mul(exponent.unsigned_abs());
+        if bits > 1_000_000 || (numerator.is_zero() && exponent < 0) {
+            return None;
+        }
+
+        // Exponentiation by squaring.
+        let mut numerator_power = BigInt::from_i64(1);
+        let mut denominator_power = BigInt::from_i64(1);
+        let mut numerator_base = numerator;
+        let mut denominator_base = denominator;
+        let mut remaining = exponent.unsigned_abs();
+        while remaining > 0 {
+            if remaining % 2 == 1 {
+                numerator_power = &numerator_power * &numerator_base;
+                denominator_power = &denominator_power * &denominator_base;
+            }
+            numerator_base = &numerator_base * &numerator_base;
+            denominator_base = &denominator_base * &denominator_base;
+            remaining /= 2;
+        }
+
+        if exponent >= 0 {
+            Some(Number::from_ratio(numerator_power, denominator_power))
+        } else {
+            Some(Number::from_ratio(denominator_power, numerator_power))
+        }
+    }
+}
+
+/// Check that a string is made of digits in the given radix, optionally
+/// separated by single underscores, and return the digits alone.
+fn remove_separators(digits: &str, radix: u32) -> Option<String> {
+    if digits.is_empty()
+        || digits.starts_with('_')
+        || digits.ends_with('_')
+        || digits.contains("__")
+    {
+        return None;
+    }
+
+    if digits.chars().all(|c| c == '_' || c.is_digit(radix)) {
+        Some(digits.chars().filter(|c| *c != '_').collect())
+    } else {
+        None
+    }
+}
+
+impl std::ops::Add for &Number {
+    type Output = Number;
+
+    fn add(self, other: &Number) -> Number {
+        self.arithmetic(
+            other,
+            i64::checked_add,
+            |(a, b), (c, d)| Number::from_ratio(&(&a * &d) + &(&c * &b), &b * &d),
+            |a, b| a + b,
+        )
+    }
+}
+
+impl std::ops::Sub for &Number {
+    type Output = Number;
+
+    fn sub(self, other: &Number) -> Number {
+        self.arithmetic(
+            other,
+            i64::checked_sub,
+            |(a, b), (c, d)| Number::from_ratio(&(&a * &d) - &(&c * &b), &b * &d),
+            |a, b| a - b,
+        )
+    }
+}
+
+impl std::ops::Mul for &Number {
+    type Output = Number;
+
+    fn mul(self, other: &Number) -> Number {
+        self.arithmetic(
+            other,
+            i64::checked_mul,
+            |(a, b), (c, d)| Number::from_ratio(&a * &c, &b * &d),
+            |a, b| a * b,
+        )
+    }
+}
+
+impl std::ops::Neg for &Number {
+    type Output = Number;
+
+    fn neg(self) -> Number {
+        match self {
+            Number::Fixnum(n) => match n.checked_neg() {
+                Some(n) => Number::Fixnum(n),
+                None => Number::Bignum(-&BigInt::from_i64(*n)),
+            },
+            Number::Bignum(n) => Number::from_bigint(-n),
+            Number::Rational(n, d) => Number::Rational(-n, d.clone()),
+            Number::Float(n) => Number::Float(-n),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    /// Compare numbers by their exact values: a float is equal to an exact
+    /// number only if it represents exactly that number. NaN is not ordered.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Fixnum(a), Number::Fixnum(b)) => Some(a.cmp(b)),
+            (Number::Float(a), Number::Float(b)) => a.partial_cmp(b),
+            (Number::Float(a), exact) => compare_float(*a, exact),
+            (exact, Number::Float(b)) => compare_float(*b, exact).map(Ordering::reverse),
+            // Denominators are always positive.
+            _ => {
+                let (a, b) = self.to_ratio().unwrap();
+                let (c, d) = other.to_ratio().unwrap();
+                Some((&a * &d).cmp(&(&c * &b)))
+            }
+        }
+    }
+}
+
+/// Compare a float with an exact number.
+fn compare_float(float: f64, exact: &Number) -> Option<Ordering> {
+    match exact {
+        // Integers up to 2^53 are represented exactly by floats.
+        Number::Fixnum(n) if n.unsigned_abs() <= 1 << 53 => float.partial_cmp(&(*n as f64)),
+        _ if float.is_nan() => None,
+        _ if float.is_infinite() => Some(if float > 0.0 {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        }),
+        _ => Number::Float(float).to_exact().unwrap().partial_cmp(exact),
+    }
+}
+
+impl std::hash::Hash for Number {
+    /// Hash consistently with equality: numbers exactly represented by a float
+    /// (e.g. `1`, `1.0` and `2/2`) hash that float, while other exact numbers,
+    /// which are equal to no float, hash their exact value.
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let f = self.to_f64();
+        let as_float = match self {
+            Number::Float(_) => true,
+            Number::Fixnum(n) => f.is_finite() && f as i128 == *n as i128,
+            _ => f.is_finite() && Number::Float(f) == *self,
+        };
+
+        if as_float {
+            let bits = if f == 0.0 {
+                0
+            } else if f.is_nan() {
+                f64::NAN.to_bits()
+            } else {
+                f.to_bits()
+            };
+            bits.hash(state);
+        } else {
+            self.to_ratio().unwrap().hash(state);
+        }
+    }
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number::Fixnum(n) => write!(f, "{}", n),
+            Number::Bignum(n) => write!(f, "{}", n),
+            Number::Rational(n, d) => write!(f, "{}/{}", n, d),
+            // Floats are always printed with a decimal point or an exponent,
+            // to tell them apart from exact integers.
+            Number::Float(n) if n.is_nan() => write!(f, "nan"),
+            Number::Float(n) if n.is_infinite() && *n > 0.0 => write!(f, "inf"),
+            Number::Float(n) if n.is_infinite() => write!(f, "-inf"),
+            Number::Float(n) => write!(f, "{:?}", n),
+        }
+    }
+}
+
+impl From<i64> for Number {
+    fn from(n: i64) -> Self {
+        Number::Fixnum(n)
+    }
+}
+
+impl From<f64> for Number {
+    fn from(n: f64) -> Self {
+        Number::Float(n)
+    }
+}
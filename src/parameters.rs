@@ -0,0 +1,354 @@
+use crate::interpreter::{Interpreter, MankaiObject, RuntimeError};
+use crate::list::List;
+use crate::parser::Sexp;
+use crate::pattern::Pattern;
+use crate::token::*;
+
+/// A parameter that may be omitted by the caller (an `&optional` or a `&key`
+/// parameter).
+#[derive(Debug, PartialEq, Clone)]
+pub struct OptionalParameter {
+    /// Name of the parameter.
+    pub identifier: Token,
+    /// Expression evaluated when the parameter is omitted. If there is none the
+    /// parameter is bound to the empty list.
+    pub default: Option<Sexp>,
+}
+
+/// The parameter list of a user-defined function.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Parameters {
+    /// Parameters that must always be given, either identifiers or
+    /// destructuring patterns.
+    pub required: Vec<Pattern>,
+    /// Positional parameters following `&optional`.
+    pub optional: Vec<OptionalParameter>,
+    /// Parameter following `&rest`, collects the extra arguments in a list.
+    pub rest: Option<Token>,
+    /// Keyword parameters following `&key`.
+    pub keys: Vec<OptionalParameter>,
+}
+
+/// Sections of a parameter list, in the order they must appear.
+#[derive(PartialEq, PartialOrd, Clone, Copy)]
+enum Section {
+    Required,
+    Optional,
+    Rest,
+    Key,
+}
+
+impl Parameters {
+    /// Make a parameter list made only of required parameters.
+    pub fn new(required: Vec<Token>) -> Self {
+        Parameters {
+            required: required.into_iter().map(Pattern::Variable).collect(),
+            ..Parameters::default()
+        }
+    }
+
+    /// Parse a parameter list such as
+    /// `(a b &optional (c 1) &rest others &key (d 2) e)`.
+    pub fn parse(list: &[Sexp]) -> Result<Self, RuntimeError> {
+        let mut parameters = Parameters::default();
+        let mut section = Section::Required;
+        let mut names: Vec<String> = Vec::new();
+
+        for (i, parameter) in list.iter().enumerate() {
+            // Handle the section markers.
+            if let Sexp::Atom(token) = parameter {
+                let next_section = match token.lexeme.as_str() {
+                    "&optional" => Some(Section::Optional),
+                    "&rest" => Some(Section::Rest),
+                    "&key" => Some(Section::Key),
+                    _ => None,
+                };
+
+                if let Some(next_section) = next_section {
+                    if next_section <= section
+                        || (section == Section::Rest && parameters.rest.is_none())
+                    {
+                        return Err(RuntimeError::new(&format!(
+                            "unexpected '{}' in list of arguments!",
+                            token.lexeme
+                        )));
+                    }
+
+                    section = next_section;
+                    continue;
+                }
+            }
+
+            // Required parameters can be destructuring patterns.
+            if section == Section::Required && !matches!(parameter, Sexp::Atom(_)) {
+                let pattern = Pattern::parse_binding(parameter)?;
+                for identifier in pattern.variables() {
+                    if names.contains(&identifier.lexeme) {
+                        return Err(RuntimeError::new(&format!(
+                            "argument '{}' appears more than once!",
+                            identifier.lexeme
+                        )));
+                    }
+                    names.push(identifier.lexeme.clone());
+                }
+
+                parameters.required.push(pattern);
+                continue;
+            }
+
+            // Get the identifier and the default value (if any) of the
+            // parameter.
+            let (identifier, default) = match parameter {
+                Sexp::Atom(token) => (token, None),
+                Sexp::List(list) if section == Section::Optional || section == Section::Key => {
+                    match list.as_slice() {
+                        [Sexp::Atom(token), default] => (token, Some(default.clone())),
+                        _ => {
+                            return Err(RuntimeError::new(&format!(
+                            "{}th argument must be an identifier or an (identifier default) pair!",
+                            i + 1
+                        )))
+                        }
+                    }
+                }
+                _ => {
+                    return Err(RuntimeError::new(&format!(
+                        "Expected list of arguments: {}th argument is not an identifier!",
+                        i + 1
+                    )))
+                }
+            };
+
+            if identifier.kind != TokenKind::Identifier {
+                return Err(RuntimeError::new(&format!(
+                    "{}th argument is not an identifier!",
+                    i + 1
+                )));
+            }
+
+            if names.contains(&identifier.lexeme) {
+                return Err(RuntimeError::new(&format!(
+                    "argument '{}' appears more than once!",
+                    identifier.lexeme
+                )));
+            }
+            names.push(identifier.lexeme.clone());
+
+            let identifier = identifier.clone();
+            match section {
+                Section::Required => parameters.required.push(Pattern::Variable(identifier)),
+                Section::Optional => parameters.optional.push(OptionalParameter {
+                    identifier,
+                    default,
+                }),
+                Section::Rest => {
+                    if parameters.rest.is_some() {
+                        return Err(RuntimeError::new(
+                            "'&rest' must be followed by exactly one identifier!",
+                        ));
+                    }
+                    parameters.rest = Some(identifier);
+                }
+                Section::Key => parameters.keys.push(OptionalParameter {
+                    identifier,
+                    default,
+                }),
+            }
+        }
+
+        if section == Section::Rest && parameters.rest.is_none() {
+            return Err(RuntimeError::new(
+                "'&rest' must be followed by exactly one identifier!",
+            ));
+        }
+
+        Ok(parameters)
+    }
+
+    /// Minimum number of arguments accepted.
+    pub fn min_arity(&self) -> usize {
+        self.required.len()
+    }
+
+    /// Maximum number of arguments accepted (`None` if unbounded).
+    pub fn max_arity(&self) -> Option<usize> {
+        if self.rest.is_some() || !self.keys.is_empty() {
+            None
+        } else {
+            Some(self.required.len() + self.optional.len())
+        }
+    }
+
+    /// Check if a call with the given number of arguments is accepted.
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min_arity()
+            && match self.max_arity() {
+                Some(max) => count <= max,
+                None => true,
+            }
+    }
+
+    /// Human readable description of the accepted number of arguments, e.g.
+    /// "1 to 3".
+    pub fn arity(&self) -> String {
+        let min = self.min_arity();
+
+        match self.max_arity() {
+            Some(max) if max == min => min.to_string(),
+            Some(max) => format!("{} to {}", min, max),
+            None => format!("at least {}", min),
+        }
+    }
+
+    /// Bind the arguments of a call in the current (innermost) scope of the
+    /// interpreter's environment. Default values are evaluated in that same
+    /// scope so they can refer to previous parameters.
+    pub fn bind(
+        &self,
+        interpreter: &mut Interpreter,
+        function_name: &str,
+        arguments: Vec<MankaiObject>,
+    ) -> Result<(), RuntimeError> {
+        // Arity check.
+        if !self.accepts(arguments.len()) {
+            return Err(RuntimeError::new(&format!(
+                "found {} arguments but '{}' expected {}!",
+                arguments.len(),
+                function_name,
+                describe_arities(&[self])
+            )));
+        }
+
+        let mut arguments = arguments.into_iter();
+
+        // Required parameters.
+        for (i, pattern) in self.required.iter().enumerate() {
+            let argument = arguments.next().unwrap();
+
+            let mut bindings = Vec::new();
+            if !pattern.matches(interpreter, &argument, &mut bindings)? {
+                return Err(RuntimeError::new(&format!(
+                    "{}-th argument to '{}' must match the pattern {} but is {}!",
+                    i + 1,
+                    function_name,
+                    pattern,
+                    argument.to_string()
+                )));
+            }
+
+            for (identifier, value) in bindings {
+                interpreter.environment.define(&identifier, value);
+            }
+        }
+
+        // Optional parameters.
+        for parameter in self.optional.iter() {
+            let value = match arguments.next() {
+                Some(value) => value,
+                None => parameter.default_value(interpreter)?,
+            };
+            interpreter.environment.define(&parameter.identifier, value);
+        }
+
+        let remaining: Vec<MankaiObject> = arguments.collect();
+
+        // Keyword parameters.
+        if !self.keys.is_empty() {
+            if remaining.len() % 2 == 1 {
+                return Err(RuntimeError::new(&format!(
+                    "odd number of keyword arguments to '{}'!",
+                    function_name
+                )));
+            }
+
+            let mut given = Vec::new();
+            for pair in remaining.chunks(2) {
+                let key = match &pair[0] {
+                    MankaiObject::Keyword(key) => key,
+                    other => {
+                        return Err(RuntimeError::new(&format!(
+                            "expected keyword argument to '{}' but found '{}'!",
+                            function_name,
+                            other.to_string()
+                        )))
+                    }
+                };
+
+                if !self.keys.iter().any(|p| p.identifier.lexeme == *key) {
+                    return Err(RuntimeError::new(&format!(
+                        "unknown keyword argument ':{}' to '{}'!",
+                        key, function_name
+                    )));
+                }
+
+                given.push((key.clone(), pair[1].clone()));
+            }
+
+            for parameter in self.keys.iter() {
+                let value = match given
+                    .iter()
+                    .find(|(key, _)| *key == parameter.identifier.lexeme)
+                {
+                    Some((_, value)) => value.clone(),
+                    None => parameter.default_value(interpreter)?,
+                };
+                interpreter.environment.define(&parameter.identifier, value);
+            }
+        }
+
+        // Rest parameter.
+        if let Some(identifier) = &self.rest {
+            interpreter
+                .environment
+                .define(identifier, MankaiObject::List(remaining.into()));
+        }
+
+        Ok(())
+    }
+}
+
+impl OptionalParameter {
+    /// Value of the parameter when it's omitted by the caller.
+    fn default_value(&self, interpreter: &mut Interpreter) -> Result<MankaiObject, RuntimeError> {
+        match &self.default {
+            Some(expr) => interpreter.evaluate(expr),
+            None => Ok(MankaiObject::List(List::new())),
+        }
+    }
+}
+
+/// Describe the number of arguments accepted by a group of parameter lists,
+/// e.g. "1, 2 or at least 4 arguments".
+pub fn describe_arities(parameter_lists: &[&Parameters]) -> String {
+    // Clauses accepting the same numbers of arguments are described once.
+    // The last number written decides between "argument" and "arguments".
+    let mut arities: Vec<String> = Vec::new();
+    let mut last_count = 0;
+    for parameters in parameter_lists {
+        let arity = parameters.arity();
+        if !arities.contains(&arity) {
+            arities.push(arity);
+            last_count = parameters
+                .max_arity()
+                .unwrap_or_else(|| parameters.min_arity());
+        }
+    }
+
+    let mut description = String::new();
+    for (i, arity) in arities.iter().enumerate() {
+        if i > 0 && i == arities.len() - 1 {
+            description.push_str(" or ");
+        } else if i > 0 {
+            description.push_str(", ");
+        }
+        description.push_str(arity);
+    }
+
+    if last_count == 1 {
+        description.push_str(" argument");
+    } else {
+        description.push_str(" arguments");
+    }
+
+    description
+}
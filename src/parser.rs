@@ -1,10 +1,19 @@
 use crate::token::*;
+use std::rc::Rc;
 
 /// An S-expression (sexp for brevity).
 #[derive(Debug, PartialEq, Clone)]
 pub enum Sexp {
     Atom(Token),
-    List(Vec<Sexp>),
+    /// A list. Its elements, like those of the literals below, are shared so
+    /// that the evaluator can hold on to a list while evaluating them.
+    List(Rc<Vec<Sexp>>),
+    /// A `{key value ...}` map literal.
+    Map(Rc<Vec<(Sexp, Sexp)>>),
+    /// A `[element ...]` vector literal.
+    Vector(Rc<Vec<Sexp>>),
+    /// A `#{element ...}` set literal.
+    Set(Rc<Vec<Sexp>>),
 }
 
 /// A parsing error.
@@ -64,32 +73,115 @@ impl Parser {
         self.tokens.get(self.current).unwrap()
     }
 
-    /// Finish parsing a list.
-    fn finish_list(&mut self) -> Result<Sexp, ParseError> {
+    /// Check if a token closes a list, a map or a vector.
+    fn is_closing(token: &Token) -> bool {
+        matches!(
+            token.kind,
+            TokenKind::RightParen | TokenKind::RightBrace | TokenKind::RightBracket
+        )
+    }
+
+    /// Skip the sexps commented out by `#;`.
+    fn skip_datum_comments(&mut self) -> Result<(), ParseError> {
+        while self.peek().kind == TokenKind::DatumComment {
+            let token = self.advance().clone();
+
+            if self.is_at_end() || Parser::is_closing(self.peek()) {
+                return Err(ParseError::new("expected a sexp to comment out", &token));
+            }
+
+            self.parse_sexp()?;
+        }
+
+        Ok(())
+    }
+
+    /// Parse the elements of a list, a map, a vector or a set up to (and including)
+    /// the `closing` token.
+    fn finish_elements(&mut self, closing: TokenKind) -> Result<Vec<Sexp>, ParseError> {
         let mut sexps = Vec::new();
-        sexps.push(self.parse_sexp()?);
 
-        while self.peek().kind != TokenKind::RightParen && !self.is_at_end() {
+        loop {
+            self.skip_datum_comments()?;
+
+            if Parser::is_closing(self.peek()) || self.is_at_end() {
+                break;
+            }
+
             sexps.push(self.parse_sexp()?);
         }
 
-        if self.peek().kind != TokenKind::RightParen {
-            Err(ParseError::new("expected ')'", self.peek()))
+        if self.peek().kind != closing {
+            let expected = match closing {
+                TokenKind::RightBrace => "expected '}'",
+                TokenKind::RightBracket => "expected ']'",
+                _ => "expected ')'",
+            };
+
+            Err(ParseError::new(expected, self.peek()))
         } else {
             self.current += 1;
-            Ok(Sexp::List(sexps))
+            Ok(sexps)
         }
     }
 
+    /// Finish parsing a list.
+    fn finish_list(&mut self) -> Result<Sexp, ParseError> {
+        Ok(Sexp::List(Rc::new(
+            self.finish_elements(TokenKind::RightParen)?,
+        )))
+    }
+
+    /// Finish parsing a vector literal.
+    fn finish_vector(&mut self) -> Result<Sexp, ParseError> {
+        Ok(Sexp::Vector(Rc::new(
+            self.finish_elements(TokenKind::RightBracket)?,
+        )))
+    }
+
+    /// Finish parsing a set literal.
+    fn finish_set(&mut self) -> Result<Sexp, ParseError> {
+        Ok(Sexp::Set(Rc::new(
+            self.finish_elements(TokenKind::RightBrace)?,
+        )))
+    }
+
+    /// Finish parsing a map literal.
+    fn finish_map(&mut self) -> Result<Sexp, ParseError> {
+        let sexps = self.finish_elements(TokenKind::RightBrace)?;
+
+        if sexps.len() % 2 == 1 {
+            return Err(ParseError::new(
+                "expected a value for every key of the map",
+                self.tokens.get(self.current - 1).unwrap(),
+            ));
+        }
+
+        let mut entries = Vec::new();
+        let mut sexps = sexps.into_iter();
+        while let (Some(key), Some(value)) = (sexps.next(), sexps.next()) {
+            entries.push((key, value));
+        }
+
+        Ok(Sexp::Map(Rc::new(entries)))
+    }
+
     /// Parse a single sexp.
     fn parse_sexp(&mut self) -> Result<Sexp, ParseError> {
+        self.skip_datum_comments()?;
+
         if self.is_at_end() {
             Err(ParseError::from_message("premature EOF!"))
         } else {
             let token = self.advance();
             match token.kind {
                 TokenKind::LeftParen => self.finish_list(),
-                TokenKind::RightParen => Err(ParseError::new("expected atom or list", token)),
+                TokenKind::LeftBrace => self.finish_map(),
+                TokenKind::LeftBracket => self.finish_vector(),
+                TokenKind::HashLeftBrace => self.finish_set(),
+                TokenKind::RightParen | TokenKind::RightBrace | TokenKind::RightBracket => Err(
+                    ParseError::new("expected atom, list, map, vector or set", token),
+                ),
                 _ => Ok(Sexp::Atom(token.clone())),
             }
         }
@@ -98,6 +190,7 @@ impl Parser {
     pub fn parse(&mut self) -> Result<Sexp, ParseError> {
         // Parse one sexp.
         let sexp = self.parse_sexp()?;
+        self.skip_datum_comments()?;
 
         if !self.is_at_end() {
             Err(ParseError::from_message(
@@ -113,6 +206,8 @@ impl Parser {
 mod parser_test {
     use super::{ParseError, Parser, Sexp, Token, TokenKind};
     use crate::lexer::Lexer;
+    use crate::number::Number;
+    use std::rc::Rc;
 
     #[test]
     fn parser_initialization_and_basic_operations() {
@@ -182,13 +277,16 @@ mod parser_test {
                     if let Sexp::Atom(token) = list.get(3).unwrap() {
                         assert_eq!(
                             token.clone(),
-                            Token::new(String::from("12.0"), TokenKind::Number(12.0))
+                            Token::new(
+                                String::from("12.0"),
+                                TokenKind::Number(Number::Float(12.0))
+                            )
                         );
                     } else {
                         panic!("expected atom!");
                     }
                 }
-                Sexp::Atom(_) => panic!("expected list!"),
+                _ => panic!("expected list!"),
             },
             Err(err) => panic!(err),
         }
@@ -214,4 +312,129 @@ mod parser_test {
         }
         if let Ok(_) = parser.parse() {}
     }
+
+    #[test]
+    fn empty_list() {
+        let mut lexer = Lexer::new(String::from("(foo ())"));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+
+        let mut parser = Parser::new(lexer.tokens);
+        match parser.parse() {
+            Ok(sexp) => assert_eq!(
+                sexp,
+                Sexp::List(Rc::new(vec![
+                    Sexp::Atom(Token::new(String::from("foo"), TokenKind::Identifier)),
+                    Sexp::List(Rc::new(Vec::new())),
+                ]))
+            ),
+            Err(err) => panic!("{}", err.message),
+        }
+    }
+
+    #[test]
+    fn datum_comments() {
+        let mut lexer = Lexer::new(String::from("#;(ignored) (a #;(b c) #; #;d e f #;g)"));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+
+        let mut parser = Parser::new(lexer.tokens);
+        assert_eq!(
+            parser.parse(),
+            Ok(Sexp::List(Rc::new(vec![
+                Sexp::Atom(Token::new(String::from("a"), TokenKind::Identifier)),
+                Sexp::Atom(Token::new(String::from("f"), TokenKind::Identifier)),
+            ])))
+        );
+
+        for source in ["(a #;)", "a #;"].iter() {
+            let mut lexer = Lexer::new(String::from(*source));
+            if let Err(err) = lexer.scan() {
+                panic!("{}", err.message);
+            }
+
+            let mut parser = Parser::new(lexer.tokens);
+            if parser.parse().is_ok() {
+                panic!("expected parsing error for '{}'!", source);
+            }
+        }
+    }
+
+    #[test]
+    fn maps_and_vectors() {
+        let mut lexer = Lexer::new(String::from("{:a 1 {} [b]}"));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+
+        let mut parser = Parser::new(lexer.tokens);
+        assert_eq!(
+            parser.parse(),
+            Ok(Sexp::Map(Rc::new(vec![
+                (
+                    Sexp::Atom(Token::new(
+                        String::from(":a"),
+                        TokenKind::Keyword(String::from("a"))
+                    )),
+                    Sexp::Atom(Token::new(
+                        String::from("1"),
+                        TokenKind::Number(Number::from(1))
+                    ))
+                ),
+                (
+                    Sexp::Map(Rc::new(Vec::new())),
+                    Sexp::Vector(Rc::new(vec![Sexp::Atom(Token::new(
+                        String::from("b"),
+                        TokenKind::Identifier
+                    ))]))
+                ),
+            ])))
+        );
+
+        for source in ["{:a}", "{:a 1", "(:a 1}", "[1 2)", "(1 2]"].iter() {
+            let mut lexer = Lexer::new(String::from(*source));
+            if let Err(err) = lexer.scan() {
+                panic!("{}", err.message);
+            }
+
+            let mut parser = Parser::new(lexer.tokens);
+            if parser.parse().is_ok() {
+                panic!("expected parsing error for '{}'!", source);
+            }
+        }
+    }
+
+    #[test]
+    fn sets() {
+        let mut lexer = Lexer::new(String::from("#{1 #{}}"));
+        if let Err(err) = lexer.scan() {
+            panic!("{}", err.message);
+        }
+
+        let mut parser = Parser::new(lexer.tokens);
+        assert_eq!(
+            parser.parse(),
+            Ok(Sexp::Set(Rc::new(vec![
+                Sexp::Atom(Token::new(
+                    String::from("1"),
+                    TokenKind::Number(Number::from(1))
+                )),
+                Sexp::Set(Rc::new(Vec::new())),
+            ])))
+        );
+
+        for source in ["#{1", "#{1 2)", "#(1)"].iter() {
+            let mut lexer = Lexer::new(String::from(*source));
+            if lexer.scan().is_err() {
+                continue;
+            }
+
+            let mut parser = Parser::new(lexer.tokens);
+            if parser.parse().is_ok() {
+                panic!("expected parsing error for '{}'!", source);
+            }
+        }
+    }
 }
@@ -0,0 +1,409 @@
+use crate::interpreter::{Interpreter, MankaiObject, RuntimeError};
+use crate::list::List;
+use crate::parser::Sexp;
+use crate::record::RecordFunctionKind;
+use crate::token::*;
+use std::rc::Rc;
+
+/// A pattern of `match!`, `let!` or a parameter list.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Pattern {
+    /// `_`: matches anything.
+    Wildcard,
+    /// An identifier: matches anything and binds it.
+    Variable(Token),
+    /// A number, string, character, keyword or boolean: matches equal values.
+    Literal(MankaiObject),
+    /// `(list p1 ... pN)` or `(list p1 ... pN &rest p)`: matches lists with
+    /// (at least) `N` elements. `()` matches the empty list.
+    List {
+        elements: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// `[p1 ... pN]` or `[p1 ... pN &rest p]`: same as lists but for vectors.
+    Vector {
+        elements: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// `{k1 p1 ... kN pN}`: matches maps having (at least) the keys `k1`, ...,
+    /// `kN`, which must be literals.
+    Map(Vec<(MankaiObject, Pattern)>),
+    /// `(name p1 ... pN)`: matches records made by the constructor `name`
+    /// (defined by `defrecord!` or `deftype!`), one pattern per field.
+    Record {
+        constructor: Token,
+        fields: Vec<Pattern>,
+    },
+}
+
+impl Pattern {
+    /// Parse a pattern, as used by `match!`. A pattern can't bind the same
+    /// identifier twice.
+    pub fn parse(sexp: &Sexp) -> Result<Self, RuntimeError> {
+        let pattern = Pattern::parse_element(sexp)?;
+
+        let variables = pattern.variables();
+        for (i, variable) in variables.iter().enumerate() {
+            if variables[..i]
+                .iter()
+                .any(|previous| previous.lexeme == variable.lexeme)
+            {
+                return Err(RuntimeError::new(&format!(
+                    "'{}' appears more than once in the pattern {}!",
+                    variable.lexeme, pattern
+                )));
+            }
+        }
+
+        Ok(pattern)
+    }
+
+    /// Parse a destructuring pattern, as used by `let!` and in parameter lists.
+    /// These are the patterns made only of identifiers, `_`, `(list ...)`
+    /// lists, `[...]` vectors and `{...}` maps, which check the shape of the
+    /// value but not its contents.
+    pub fn parse_binding(sexp: &Sexp) -> Result<Self, RuntimeError> {
+        let pattern = Pattern::parse(sexp)?;
+        if !pattern.is_destructuring() {
+            return Err(RuntimeError::new(&format!(
+                "invalid destructuring pattern '{}': only identifiers, _, (list ...), [...] and {{...}} can be used!",
+                sexp_to_string(sexp)
+            )));
+        }
+
+        Ok(pattern)
+    }
+
+    /// Parse a pattern without checking for repeated identifiers.
+    fn parse_element(sexp: &Sexp) -> Result<Self, RuntimeError> {
+        if let Some(literal) = literal(sexp) {
+            return Ok(Pattern::Literal(literal));
+        }
+
+        match sexp {
+            Sexp::Atom(token) if token.lexeme == "_" => Ok(Pattern::Wildcard),
+            Sexp::Atom(token) if token.kind == TokenKind::Identifier => {
+                Ok(Pattern::Variable(token.clone()))
+            }
+            Sexp::List(list) => match list.split_first() {
+                None => Ok(Pattern::List {
+                    elements: Vec::new(),
+                    rest: None,
+                }),
+                Some((Sexp::Atom(head), elements)) if head.kind == TokenKind::Identifier => {
+                    if head.lexeme == "list" {
+                        let (elements, rest) = parse_sequence(elements, Pattern::parse_element)?;
+                        Ok(Pattern::List { elements, rest })
+                    } else {
+                        Ok(Pattern::Record {
+                            constructor: head.clone(),
+                            fields: elements
+                                .iter()
+                                .map(Pattern::parse_element)
+                                .collect::<Result<_, _>>()?,
+                        })
+                    }
+                }
+                _ => Err(RuntimeError::new(&format!(
+                    "invalid pattern '{}'!",
+                    sexp_to_string(sexp)
+                ))),
+            },
+            Sexp::Vector(elements) => {
+                let (elements, rest) = parse_sequence(elements, Pattern::parse_element)?;
+                Ok(Pattern::Vector { elements, rest })
+            }
+            Sexp::Map(entries) => parse_map(entries, Pattern::parse_element),
+            _ => Err(RuntimeError::new(&format!(
+                "invalid pattern '{}'!",
+                sexp_to_string(sexp)
+            ))),
+        }
+    }
+
+    /// Check if the pattern is made only of variables, wildcards, lists,
+    /// vectors and maps.
+    fn is_destructuring(&self) -> bool {
+        match self {
+            Pattern::Wildcard | Pattern::Variable(_) => true,
+            Pattern::Literal(_) | Pattern::Record { .. } => false,
+            Pattern::List { elements, rest } | Pattern::Vector { elements, rest } => elements
+                .iter()
+                .chain(rest.iter().map(|rest| rest.as_ref()))
+                .all(Pattern::is_destructuring),
+            Pattern::Map(entries) => entries
+                .iter()
+                .all(|(_, pattern)| pattern.is_destructuring()),
+        }
+    }
+
+    /// Identifiers bound by the pattern, in order.
+    pub fn variables(&self) -> Vec<&Token> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => Vec::new(),
+            Pattern::Variable(identifier) => vec![identifier],
+            Pattern::List { elements, rest } | Pattern::Vector { elements, rest } => elements
+                .iter()
+                .chain(rest.iter().map(|rest| rest.as_ref()))
+                .flat_map(Pattern::variables)
+                .collect(),
+            Pattern::Map(entries) => entries
+                .iter()
+                .flat_map(|(_, pattern)| pattern.variables())
+                .collect(),
+            Pattern::Record { fields, .. } => fields.iter().flat_map(Pattern::variables).collect(),
+        }
+    }
+
+    /// Match a value against the pattern, pushing the bindings of its variables
+    /// to `bindings`. Returns whether the value matches.
+    pub fn matches(
+        &self,
+        interpreter: &Interpreter,
+        value: &MankaiObject,
+        bindings: &mut Vec<(Token, MankaiObject)>,
+    ) -> Result<bool, RuntimeError> {
+        match (self, value) {
+            (Pattern::Wildcard, _) => Ok(true),
+            (Pattern::Variable(identifier), _) => {
+                bindings.push((identifier.clone(), value.clone()));
+                Ok(true)
+            }
+            (Pattern::Literal(literal), _) => Ok(literal == value),
+            (Pattern::List { elements, rest }, MankaiObject::List(list)) => {
+                if !length_matches(elements, rest, list.len()) {
+                    return Ok(false);
+                }
+
+                for (pattern, element) in elements.iter().zip(list.iter()) {
+                    if !pattern.matches(interpreter, element, bindings)? {
+                        return Ok(false);
+                    }
+                }
+
+                match rest {
+                    Some(pattern) => {
+                        let rest: List = list.iter().skip(elements.len()).cloned().collect();
+                        pattern.matches(interpreter, &MankaiObject::List(rest), bindings)
+                    }
+                    None => Ok(true),
+                }
+            }
+            (Pattern::Vector { elements, rest }, MankaiObject::Vector(vector)) => {
+                if !length_matches(elements, rest, vector.len()) {
+                    return Ok(false);
+                }
+
+                for (pattern, element) in elements.iter().zip(vector.iter()) {
+                    if !pattern.matches(interpreter, element, bindings)? {
+                        return Ok(false);
+                    }
+                }
+
+                match rest {
+                    Some(pattern) => {
+                        let rest = Rc::new(vector[elements.len()..].to_vec());
+                        pattern.matches(interpreter, &MankaiObject::Vector(rest), bindings)
+                    }
+                    None => Ok(true),
+                }
+            }
+            (Pattern::Map(entries), MankaiObject::Map(map)) => {
+                for (key, pattern) in entries {
+                    match map.get(key) {
+                        Some(value) => {
+                            if !pattern.matches(interpreter, value, bindings)? {
+                                return Ok(false);
+                            }
+                        }
+                        None => return Ok(false),
+                    }
+                }
+
+                Ok(true)
+            }
+            (
+                Pattern::Record {
+                    constructor,
+                    fields,
+                },
+                _,
+            ) => {
+                let record_type = match interpreter.environment.get(constructor)? {
+                    MankaiObject::RecordFunction(function)
+                        if function.kind == RecordFunctionKind::Constructor =>
+                    {
+                        function.record_type
+                    }
+                    _ => {
+                        return Err(RuntimeError::new(&format!(
+                            "'{}' in pattern is not a record constructor!",
+                            constructor.lexeme
+                        )))
+                    }
+                };
+
+                if fields.len() != record_type.fields.len() {
+                    return Err(RuntimeError::new(&format!(
+                        "pattern for '{}' must have {} field{} but has {}!",
+                        constructor.lexeme,
+                        record_type.fields.len(),
+                        if record_type.fields.len() == 1 {
+                            ""
+                        } else {
+                            "s"
+                        },
+                        fields.len()
+                    )));
+                }
+
+                match value {
+                    MankaiObject::Record(record) if record.is_a(&record_type) => {
+                        for (pattern, value) in fields.iter().zip(record.values.iter()) {
+                            if !pattern.matches(interpreter, value, bindings)? {
+                                return Ok(false);
+                            }
+                        }
+
+                        Ok(true)
+                    }
+                    _ => Ok(false),
+                }
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    /// Write the pattern back as source code.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = match self {
+            Pattern::Wildcard => String::from("_"),
+            Pattern::Variable(identifier) => identifier.lexeme.clone(),
+            Pattern::Literal(literal) => literal.to_string(),
+            Pattern::List { elements, rest } if elements.is_empty() && rest.is_none() => {
+                String::from("()")
+            }
+            Pattern::List { elements, rest } => {
+                format!("(list {})", join_patterns(elements, rest))
+            }
+            Pattern::Vector { elements, rest } => format!("[{}]", join_patterns(elements, rest)),
+            Pattern::Map(entries) => format!(
+                "{{{}}}",
+                entries
+                    .iter()
+                    .map(|(key, pattern)| format!("{} {}", key.to_string(), pattern))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            Pattern::Record {
+                constructor,
+                fields,
+            } => format!("({} {})", constructor.lexeme, join_patterns(fields, &None)),
+        };
+
+        write!(f, "{}", source)
+    }
+}
+
+/// Write element patterns, and the rest pattern if any, separated by spaces.
+fn join_patterns(elements: &[Pattern], rest: &Option<Box<Pattern>>) -> String {
+    let mut parts: Vec<String> = elements.iter().map(Pattern::to_string).collect();
+    if let Some(rest) = rest {
+        parts.push(String::from("&rest"));
+        parts.push(rest.to_string());
+    }
+
+    parts.join(" ")
+}
+
+/// Value of a literal pattern, if the sexp is one.
+fn literal(sexp: &Sexp) -> Option<MankaiObject> {
+    match sexp {
+        Sexp::Atom(token) => match &token.kind {
+            TokenKind::Number(n) => Some(MankaiObject::Number(n.clone())),
+            TokenKind::String(s) => Some(MankaiObject::String(s.as_str().into())),
+            TokenKind::Char(c) => Some(MankaiObject::Char(*c)),
+            TokenKind::Keyword(k) => Some(MankaiObject::Keyword(k.clone())),
+            TokenKind::Identifier if token.lexeme == "true" => Some(MankaiObject::Bool(true)),
+            TokenKind::Identifier if token.lexeme == "false" => Some(MankaiObject::Bool(false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Parse the entries of a map pattern, whose keys must be literals.
+fn parse_map(
+    entries: &[(Sexp, Sexp)],
+    parse: fn(&Sexp) -> Result<Pattern, RuntimeError>,
+) -> Result<Pattern, RuntimeError> {
+    let mut parsed = Vec::new();
+    for (key, value) in entries {
+        match literal(key) {
+            Some(key) => parsed.push((key, parse(value)?)),
+            None => {
+                return Err(RuntimeError::new(&format!(
+                    "keys of map patterns must be literals, found '{}'!",
+                    sexp_to_string(key)
+                )))
+            }
+        }
+    }
+
+    Ok(Pattern::Map(parsed))
+}
+
+/// Parse the element patterns of a list or vector pattern, followed by an
+/// optional `&rest pattern`.
+fn parse_sequence(
+    sexps: &[Sexp],
+    parse: fn(&Sexp) -> Result<Pattern, RuntimeError>,
+) -> Result<(Vec<Pattern>, Option<Box<Pattern>>), RuntimeError> {
+    let rest_marker = sexps
+        .iter()
+        .position(|sexp| matches!(sexp, Sexp::Atom(token) if token.lexeme == "&rest"));
+
+    let (elements, rest) = match rest_marker {
+        Some(i) => match &sexps[i + 1..] {
+            [rest] => (&sexps[..i], Some(Box::new(parse(rest)?))),
+            _ => {
+                return Err(RuntimeError::new(
+                    "'&rest' in a pattern must be followed by exactly one pattern!",
+                ))
+            }
+        },
+        None => (sexps, None),
+    };
+
+    Ok((elements.iter().map(parse).collect::<Result<_, _>>()?, rest))
+}
+
+/// Check if a sequence of the given length can match element patterns
+/// followed by an optional rest pattern.
+fn length_matches(elements: &[Pattern], rest: &Option<Box<Pattern>>, length: usize) -> bool {
+    match rest {
+        Some(_) => length >= elements.len(),
+        None => length == elements.len(),
+    }
+}
+
+/// Write a sexp back as source code, for error messages.
+fn sexp_to_string(sexp: &Sexp) -> String {
+    match sexp {
+        Sexp::Atom(token) => token.lexeme.clone(),
+        Sexp::List(list) => format!("({})", join(list.iter())),
+        Sexp::Vector(elements) => format!("[{}]", join(elements.iter())),
+        Sexp::Set(elements) => format!("#{{{}}}", join(elements.iter())),
+        Sexp::Map(entries) => format!(
+            "{{{}}}",
+            join(entries.iter().flat_map(|(key, value)| vec![key, value]))
+        ),
+    }
+}
+
+/// Write sexps separated by spaces.
+fn join<'a>(sexps: impl Iterator<Item = &'a Sexp>) -> String {
+    sexps.map(sexp_to_string).collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,177 @@
+use crate::interpreter::{MankaiObject, RuntimeError};
+use std::rc::Rc;
+
+/// A record type defined with `defrecord!`, or a variant of a type defined
+/// with `deftype!`.
+#[derive(Debug)]
+pub struct RecordType {
+    /// Name of the type.
+    pub name: String,
+    /// Names of the fields, in order.
+    pub fields: Vec<String>,
+    /// Name of the type defined with `deftype!` this is a variant of, shared by
+    /// all of its variants.
+    pub family: Option<Rc<str>>,
+}
+
+/// A value of a record type.
+#[derive(Clone)]
+pub struct Record {
+    /// Type of the record.
+    pub record_type: Rc<RecordType>,
+    /// Values of the fields, in the order of the type's fields.
+    pub values: Rc<Vec<MankaiObject>>,
+}
+
+impl Record {
+    /// Check if the record is of the given type.
+    pub fn is_a(&self, record_type: &Rc<RecordType>) -> bool {
+        Rc::ptr_eq(&self.record_type, record_type)
+    }
+
+    /// Check if the record is a variant of the given `deftype!` type.
+    pub fn is_variant_of(&self, family: &Rc<str>) -> bool {
+        match &self.record_type.family {
+            Some(own) => Rc::ptr_eq(own, family),
+            None => false,
+        }
+    }
+}
+
+impl PartialEq for Record {
+    /// Two records are equal if they have the same type and equal fields.
+    fn eq(&self, other: &Self) -> bool {
+        self.is_a(&other.record_type) && self.values == other.values
+    }
+}
+
+impl std::fmt::Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#<{}", self.record_type.name)?;
+        for (field, value) in self.record_type.fields.iter().zip(self.values.iter()) {
+            write!(f, " {}: {}", field, value.to_string())?;
+        }
+
+        write!(f, ">")
+    }
+}
+
+/// What a function generated by `defrecord!` does.
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecordFunctionKind {
+    /// Make a record from the values of its fields.
+    Constructor,
+    /// Check if a value is a record of the type.
+    Predicate,
+    /// Check if a value is a variant of the `deftype!` type the type belongs
+    /// to.
+    FamilyPredicate,
+    /// Get the value of the field at the given position.
+    Accessor(usize),
+    /// Copy a record with a new value for the field at the given position.
+    Updater(usize),
+}
+
+/// A function generated by `defrecord!`.
+#[derive(Clone)]
+pub struct RecordFunction {
+    /// Type the function works on.
+    pub record_type: Rc<RecordType>,
+    /// What the function does.
+    pub kind: RecordFunctionKind,
+}
+
+impl RecordFunctionKind {
+    /// Position of the kind among the functions generated for a type.
+    pub fn index(self) -> usize {
+        match self {
+            RecordFunctionKind::Constructor => 0,
+            RecordFunctionKind::Predicate => 1,
+            RecordFunctionKind::FamilyPredicate => 2,
+            RecordFunctionKind::Accessor(i) => 3 + 2 * i,
+            RecordFunctionKind::Updater(i) => 4 + 2 * i,
+        }
+    }
+}
+
+impl RecordFunction {
+    /// Name the function is bound to: `name`, `name?`, `name-field` and
+    /// `name-with-field` for a type `name`, `family?` for a variant of the
+    /// `deftype!` type `family`.
+    pub fn name(&self) -> String {
+        let record_type = &self.record_type;
+        match self.kind {
+            RecordFunctionKind::Constructor => record_type.name.clone(),
+            RecordFunctionKind::Predicate => format!("{}?", record_type.name),
+            RecordFunctionKind::FamilyPredicate => {
+                format!("{}?", record_type.family.as_ref().unwrap())
+            }
+            RecordFunctionKind::Accessor(i) => {
+                format!("{}-{}", record_type.name, record_type.fields[i])
+            }
+            RecordFunctionKind::Updater(i) => {
+                format!("{}-with-{}", record_type.name, record_type.fields[i])
+            }
+        }
+    }
+
+    /// Call the function with arguments.
+    pub fn call(&self, arguments: Vec<MankaiObject>) -> Result<MankaiObject, RuntimeError> {
+        let name = self.name();
+
+        // Check arity.
+        let arity = match self.kind {
+            RecordFunctionKind::Constructor => self.record_type.fields.len(),
+            RecordFunctionKind::Predicate
+            | RecordFunctionKind::FamilyPredicate
+            | RecordFunctionKind::Accessor(_) => 1,
+            RecordFunctionKind::Updater(_) => 2,
+        };
+        if arguments.len() != arity {
+            return Err(RuntimeError::new(&format!(
+                "'{}' requires exactly {} argument{}!",
+                name,
+                arity,
+                if arity == 1 { "" } else { "s" }
+            )));
+        }
+
+        if self.kind == RecordFunctionKind::Constructor {
+            return Ok(MankaiObject::Record(Record {
+                record_type: self.record_type.clone(),
+                values: Rc::new(arguments),
+            }));
+        }
+
+        if self.kind == RecordFunctionKind::FamilyPredicate {
+            let family = self.record_type.family.as_ref().unwrap();
+            return Ok(MankaiObject::Bool(matches!(
+                arguments.first().unwrap(),
+                MankaiObject::Record(record) if record.is_variant_of(family)
+            )));
+        }
+
+        let record = match arguments.first().unwrap() {
+            MankaiObject::Record(record) if record.is_a(&self.record_type) => Some(record),
+            _ => None,
+        };
+
+        match (self.kind, record) {
+            (RecordFunctionKind::Predicate, record) => Ok(MankaiObject::Bool(record.is_some())),
+            (RecordFunctionKind::Accessor(i), Some(record)) => Ok(record.values[i].clone()),
+            (RecordFunctionKind::Updater(i), Some(record)) => {
+                let mut values = record.values.as_ref().clone();
+                values[i] = arguments.get(1).unwrap().clone();
+
+                Ok(MankaiObject::Record(Record {
+                    record_type: self.record_type.clone(),
+                    values: Rc::new(values),
+                }))
+            }
+            _ => Err(RuntimeError::new(&format!(
+                "1st argument to '{}' must be a '{}' record!",
+                name, self.record_type.name
+            ))),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::interpreter::MankaiObject;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+struct ReferenceCell {
+    value: RefCell<MankaiObject>,
+    /// Whether the reference is being printed, so that a reference holding
+    /// itself is printed only once.
+    printing: Cell<bool>,
+}
+
+/// A mutable reference shared by all its copies, used by boxes and atoms.
+#[derive(Clone)]
+pub struct Reference(Rc<ReferenceCell>);
+
+impl Reference {
+    /// Make a reference holding a value.
+    pub fn new(value: MankaiObject) -> Self {
+        Reference(Rc::new(ReferenceCell {
+            value: RefCell::new(value),
+            printing: Cell::new(false),
+        }))
+    }
+
+    /// Get the value held by the reference.
+    pub fn get(&self) -> MankaiObject {
+        self.0.value.borrow().clone()
+    }
+
+    /// Change the value held by the reference.
+    pub fn set(&self, value: MankaiObject) {
+        self.0.value.replace(value);
+    }
+
+    /// Print the reference as `<kind value>`, or as `<kind ...>` inside its own
+    /// value.
+    pub fn to_string(&self, kind: &str) -> String {
+        if self.0.printing.replace(true) {
+            return format!("<{} ...>", kind);
+        }
+
+        let string = format!("<{} {}>", kind, self.get().to_string());
+        self.0.printing.set(false);
+
+        string
+    }
+
+    /// Check if two references are the same object.
+    pub fn ptr_eq(&self, other: &Reference) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Address of the reference, identifying it.
+    pub fn address(&self) -> usize {
+        Rc::as_ptr(&self.0) as usize
+    }
+}
@@ -0,0 +1,103 @@
+use crate::interpreter::MankaiObject;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// An insertion-ordered set of Mankai values. Elements are compared with
+/// Mankai equality and looked up by hash.
+#[derive(Clone, Default)]
+pub struct Set {
+    /// Elements, in insertion order and without duplicates. They're shared
+    /// between copies of the set until one of them is modified.
+    elements: Rc<Vec<MankaiObject>>,
+    /// Positions in `elements` of the elements with a given hash.
+    index: Rc<HashMap<u64, Vec<usize>>>,
+}
+
+/// Hash of a value.
+fn hash_of(value: &MankaiObject) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Set {
+    /// Make a new empty set.
+    pub fn new() -> Self {
+        Set::default()
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Check if the set has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Check if a value is an element of the set.
+    pub fn contains(&self, value: &MankaiObject) -> bool {
+        match self.index.get(&hash_of(value)) {
+            Some(positions) => positions.iter().any(|&i| self.elements[i] == *value),
+            None => false,
+        }
+    }
+
+    /// Add an element, if it's not already in the set.
+    pub fn insert(&mut self, value: MankaiObject) {
+        if self.contains(&value) {
+            return;
+        }
+
+        Rc::make_mut(&mut self.index)
+            .entry(hash_of(&value))
+            .or_default()
+            .push(self.elements.len());
+        Rc::make_mut(&mut self.elements).push(value);
+    }
+
+    /// Remove an element from the set.
+    pub fn remove(&mut self, value: &MankaiObject) {
+        if !self.contains(value) {
+            return;
+        }
+
+        // Positions of the following elements change, so rebuild the index.
+        let elements: Vec<MankaiObject> = self
+            .elements
+            .iter()
+            .filter(|element| *element != value)
+            .cloned()
+            .collect();
+        *self = Set::new();
+        for element in elements {
+            self.insert(element);
+        }
+    }
+
+    /// Check if two sets are the same object, i.e. copies of one set.
+    pub fn ptr_eq(&self, other: &Set) -> bool {
+        Rc::ptr_eq(&self.elements, &other.elements)
+    }
+
+    /// Iterate over the elements in insertion order.
+    pub fn iter(&self) -> std::slice::Iter<'_, MankaiObject> {
+        self.elements.iter()
+    }
+
+    /// Check if all the elements of the set are in `other`.
+    pub fn is_subset(&self, other: &Set) -> bool {
+        self.len() <= other.len() && self.iter().all(|element| other.contains(element))
+    }
+}
+
+impl PartialEq for Set {
+    /// Two sets are equal if they have the same elements, no matter the
+    /// insertion order.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
@@ -1,68 +1,113 @@
 use crate::interpreter::*;
+use crate::lazy::{LazySeq, Promise};
+use crate::machine::Continuation;
+use crate::parameters::Parameters;
 use crate::parser::Sexp;
+use crate::pattern::Pattern;
+use crate::record::{RecordFunction, RecordFunctionKind, RecordType};
 use crate::token::*;
+use std::rc::Rc;
 
-/// The `define!` special form.
-pub fn define(
+/// The `case-lambda!` special form. Returns a Mankai function that dispatches
+/// on the number of arguments among the given clauses.
+pub fn case_lambda(
     interpreter: &mut Interpreter,
     arguments: Vec<&Sexp>,
 ) -> Result<MankaiObject, RuntimeError> {
-    // Check that we have exactly two arguments.
-    if arguments.len() != 2 {
+    // Arity check.
+    if arguments.is_empty() {
         return Err(RuntimeError::new(
-            "expected exacly two arguments to 'define!'",
+            "'case-lambda!' requires at least one clause!",
         ));
     }
 
-    // Get token that identifies the name of the variable. Return an error if
-    // trying to define! a special form of a native function or if the token is not
-    // an identifier.
-    let name = match arguments.get(0).unwrap() {
-        Sexp::Atom(token) => token,
-        Sexp::List(_) => {
-            return Err(RuntimeError::new(
-                "expected identifier as first argument to 'define!'",
-            ))
-        }
-    };
-
-    if let TokenKind::Identifier = name.kind {
+    // Return the function.
+    Ok(MankaiObject::Function {
+        name: None,
+        clauses: Rc::new(parse_clauses("case-lambda!", &arguments)?),
+        captured: Rc::new(interpreter.environment.locals()),
+    })
+}
 
-    } else {
-        return Err(RuntimeError::new(&format!(
-            "'{}' is not an identifier!",
-            name.lexeme
-        )));
+/// The `defrecord!` special form. Defines a record type with the given fields
+/// and binds its constructor, its predicate, an accessor and an updater for
+/// each field. Returns the constructor.
+pub fn defrecord(
+    interpreter: &mut Interpreter,
+    arguments: Vec<&Sexp>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Arity check.
+    if arguments.is_empty() {
+        return Err(RuntimeError::new(
+            "'defrecord!' requires a name followed by the names of the fields!",
+        ));
     }
 
-    if interpreter.is_special_form(name) {
-        return Err(RuntimeError::new(&format!(
-            "can't assign to '{}' because the name is reserved for a special form!",
-            name.lexeme
-        )));
-    }
+    // Define the type and its functions.
+    let record_type = parse_record_type("defrecord!", &arguments, None)?;
+    define_record_functions(interpreter, record_functions(&record_type))?;
 
-    if interpreter.is_native_fucntion(name) {
-        return Err(RuntimeError::new(&format!(
-            "can't assign to '{}' because the name is reserved for a native function!",
-            name.lexeme
-        )));
+    Ok(MankaiObject::RecordFunction(RecordFunction {
+        record_type,
+        kind: RecordFunctionKind::Constructor,
+    }))
+}
+
+/// The `deftype!` special form. Defines a type made of many variants, each
+/// one with its own fields, and binds the functions of every variant (as
+/// `defrecord!` does) and the type predicate. Returns the type predicate.
+pub fn deftype(
+    interpreter: &mut Interpreter,
+    arguments: Vec<&Sexp>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Arity check.
+    if arguments.len() < 2 {
+        return Err(RuntimeError::new(
+            "'deftype!' requires a name followed by at least one variant!",
+        ));
     }
 
-    if interpreter.is_constant(name) {
-        return Err(RuntimeError::new(&format!(
-            "can't assign to '{}' because the name is reserved for a constant!",
-            name.lexeme
-        )));
+    // Get the name of the type.
+    let family: Rc<str> = match arguments.first().unwrap() {
+        Sexp::Atom(Token {
+            lexeme,
+            kind: TokenKind::Identifier,
+        }) => lexeme.as_str().into(),
+        _ => {
+            return Err(RuntimeError::new(
+                "1st argument to 'deftype!' must be an identifier!",
+            ))
+        }
+    };
+
+    // Parse all the variants before defining anything.
+    let mut variants = Vec::new();
+    for (i, argument) in arguments.iter().enumerate().skip(1) {
+        match argument {
+            Sexp::List(list) if !list.is_empty() => {
+                let names: Vec<&Sexp> = list.iter().collect();
+                variants.push(parse_record_type("deftype!", &names, Some(family.clone()))?);
+            }
+            _ => {
+                return Err(RuntimeError::new(&format!(
+                "{}-th argument to 'deftype!' must be a variant such as (name field1 ... fieldN)!",
+                i + 1
+            )))
+            }
+        }
     }
 
-    // Get the value to assign.
-    let value = interpreter.evaluate(arguments.get(1).unwrap())?;
-    let value_clone = value.clone();
+    let predicate = RecordFunction {
+        record_type: variants.first().unwrap().clone(),
+        kind: RecordFunctionKind::FamilyPredicate,
+    };
+    let mut functions = vec![predicate.clone()];
+    for variant in variants.iter() {
+        functions.extend(record_functions(variant));
+    }
+    define_record_functions(interpreter, functions)?;
 
-    // Perform the binding.
-    interpreter.environment.define(name, value);
-    Ok(value_clone)
+    Ok(MankaiObject::RecordFunction(predicate))
 }
 
 /// The 'defun!' special form.
@@ -71,9 +116,9 @@ pub fn defun(
     arguments: Vec<&Sexp>,
 ) -> Result<MankaiObject, RuntimeError> {
     // Arity check.
-    if arguments.len() != 3 {
+    if arguments.len() < 2 {
         return Err(RuntimeError::new(
-            "'defun!' requires exactly three arguments!",
+            "'defun!' requires a name followed by a list of arguments and a body or by a list of clauses!",
         ));
     }
 
@@ -95,45 +140,35 @@ pub fn defun(
         }
     };
 
-    // Get vector of identifiers for the arguments of the function.
-    let mut arguments_identifiers = Vec::new();
-    let arguments_raw = match arguments.get(1).unwrap() {
-        Sexp::List(list) => list,
-        _ => {
+    // Get the clauses of the function: either a single parameter list and
+    // body or many (parameter list, body) clauses.
+    let clauses = if arguments.iter().skip(1).all(|argument| is_clause(argument)) {
+        parse_clauses("defun!", &arguments[1..])?
+    } else {
+        if arguments.len() != 3 {
             return Err(RuntimeError::new(
-                "2nd argument to 'defun!' must be a list of identifiers!",
-            ))
+                "'defun!' requires exactly three arguments!",
+            ));
         }
-    };
-    for (i, identifier) in arguments_raw.iter().enumerate() {
-        match identifier {
-            Sexp::Atom(token) => {
-                if let TokenKind::Identifier = token.kind {
-                    arguments_identifiers.push(token.clone());
-                } else {
-                    return Err(RuntimeError::new(&format!(
-                        "{}th argument is not an identifier!",
-                        i + 1
-                    )));
-                }
-            }
+
+        let parameters = match arguments.get(1).unwrap() {
+            Sexp::List(list) => Parameters::parse(list)?,
             _ => {
-                return Err(RuntimeError::new(&format!(
-                    "Expected list of arguments: {}th argument is not an identifier!",
-                    i + 1
-                )));
+                return Err(RuntimeError::new(
+                    "2nd argument to 'defun!' must be a list of identifiers!",
+                ))
             }
-        }
-    }
+        };
 
-    // Get the body of the function.
-    let body = (*arguments.get(2).unwrap()).clone();
+        let body = (*arguments.get(2).unwrap()).clone();
+        vec![Clause { parameters, body }]
+    };
 
     // Construct the function
     let function = MankaiObject::Function {
-        name: Some(name.clone()),
-        arguments_identifiers,
-        body,
+        name: Some(name.as_str().into()),
+        clauses: Rc::new(clauses),
+        captured: Rc::new(interpreter.environment.locals()),
     };
     let function_clone = function.clone();
 
@@ -144,32 +179,27 @@ pub fn defun(
     Ok(function_clone)
 }
 
-/// The `if!` special form.
-pub fn if_special_form(
+/// The `delay!` special form. Returns a promise to evaluate the expression
+/// the first time it's forced, seeing the local bindings visible now.
+pub fn delay(
     interpreter: &mut Interpreter,
     arguments: Vec<&Sexp>,
 ) -> Result<MankaiObject, RuntimeError> {
-    // Check that we have exactly three arguments.
-    if arguments.len() != 3 {
-        return Err(RuntimeError::new("'if!' requires exactly three arguments!"));
+    // Arity check.
+    if arguments.len() != 1 {
+        return Err(RuntimeError::new("'delay!' requires exactly one argument!"));
     }
 
-    // Evaluate the condition.
-    let condition = interpreter.evaluate(arguments.get(0).unwrap())?;
-
-    // Evaluate the "then" or the "else" branch accordingly.
-    match condition {
-        MankaiObject::Bool(true) => interpreter.evaluate(arguments.get(1).unwrap()),
-        MankaiObject::Bool(false) => interpreter.evaluate(arguments.get(2).unwrap()),
-        _ => Err(RuntimeError::new(
-            "1st argument to 'if!' must evaluate to a boolean!",
-        )),
-    }
+    Ok(MankaiObject::Promise(Promise::delay(
+        interpreter,
+        arguments.first().unwrap(),
+    )))
 }
 
-/// The `lambda!` special form. Returns a Mankai function.
+/// The `lambda!` special form. Returns a Mankai function seeing the local
+/// bindings visible now.
 pub fn lambda(
-    _interpreter: &mut Interpreter,
+    interpreter: &mut Interpreter,
     arguments: Vec<&Sexp>,
 ) -> Result<MankaiObject, RuntimeError> {
     // Arity check.
@@ -179,32 +209,15 @@ pub fn lambda(
         ));
     }
 
-    // Get vector of identifiers for the arguments of the function.
-    let mut arguments_identifiers = Vec::new();
-    let arguments_raw = match arguments.get(0).unwrap() {
-        Sexp::List(list) => list,
-        _ => return Err(RuntimeError::new("1st argumeth")),
-    };
-    for (i, identifier) in arguments_raw.iter().enumerate() {
-        match identifier {
-            Sexp::Atom(token) => {
-                if let TokenKind::Identifier = token.kind {
-                    arguments_identifiers.push(token.clone());
-                } else {
-                    return Err(RuntimeError::new(&format!(
-                        "{}th argument is not an identifier!",
-                        i + 1
-                    )));
-                }
-            }
-            _ => {
-                return Err(RuntimeError::new(&format!(
-                    "Expected list of arguments: {}th argument is not an identifier!",
-                    i + 1
-                )));
-            }
+    // Get the parameter list of the function.
+    let parameters = match arguments.first().unwrap() {
+        Sexp::List(list) => Parameters::parse(list)?,
+        _ => {
+            return Err(RuntimeError::new(
+                "1st argument to 'lambda!' must be a list of identifiers!",
+            ))
         }
-    }
+    };
 
     // Get the body of the function.
     let body = (*arguments.get(1).unwrap()).clone();
@@ -212,16 +225,139 @@ pub fn lambda(
     // Return the function.
     Ok(MankaiObject::Function {
         name: None,
-        arguments_identifiers,
-        body,
+        clauses: Rc::new(vec![Clause { parameters, body }]),
+        captured: Rc::new(interpreter.environment.locals()),
     })
 }
 
-/// The 'set!' special form.
-pub fn set(
+/// The `lazy-cons!` special form. Returns a lazy sequence made of the value of
+/// its first argument followed by the elements of its second argument, a list
+/// or a lazy sequence which is only evaluated when they're needed.
+pub fn lazy_cons(
+    interpreter: &mut Interpreter,
+    arguments: Vec<&Sexp>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Arity check.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'lazy-cons!' requires exactly two arguments!",
+        ));
+    }
+
+    let head = interpreter.evaluate(arguments.first().unwrap())?;
+    Ok(MankaiObject::LazySeq(LazySeq::cons(
+        interpreter,
+        head,
+        arguments.get(1).unwrap(),
+    )))
+}
+
+/// The `let/ec!` special form. Binds an escape continuation to a name in a
+/// new layer of the environment and evaluates the body there: calling the
+/// continuation while the body runs makes `let/ec!` return the value given.
+pub fn let_ec(
     interpreter: &mut Interpreter,
     arguments: Vec<&Sexp>,
 ) -> Result<MankaiObject, RuntimeError> {
+    // Arity check.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'let/ec!' requires exactly two arguments!",
+        ));
+    }
+
+    let name = match arguments.first().unwrap() {
+        Sexp::Atom(token) if token.kind == TokenKind::Identifier => token,
+        _ => {
+            return Err(RuntimeError::new(
+                "1st argument to 'let/ec!' must be an identifier!",
+            ))
+        }
+    };
+
+    let escape = Continuation::escape();
+    interpreter.environment.extend();
+    interpreter
+        .environment
+        .define(name, MankaiObject::Continuation(escape.clone()));
+    let result = interpreter.evaluate(arguments.get(1).unwrap());
+    interpreter.environment.restrict();
+
+    escape.catch(result)
+}
+
+/// The `parameterize!` special form. Gives new values to parameters while the
+/// body is evaluated, and gives them back their previous values afterwards,
+/// even when the body is left by an error or by calling a continuation.
+pub fn parameterize(
+    interpreter: &mut Interpreter,
+    arguments: Vec<&Sexp>,
+) -> Result<MankaiObject, RuntimeError> {
+    // Arity check.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new(
+            "'parameterize!' requires exactly two arguments!",
+        ));
+    }
+
+    let bindings = match arguments.first().unwrap() {
+        Sexp::List(bindings) => bindings,
+        _ => {
+            return Err(RuntimeError::new(
+                "1st argument to 'parameterize!' must be a list of (parameter value) pairs!",
+            ))
+        }
+    };
+
+    // Evaluate every parameter and value before changing any of them.
+    let mut values = Vec::new();
+    for (i, binding) in bindings.iter().enumerate() {
+        let pair =
+            match binding {
+                Sexp::List(pair) if pair.len() == 2 => pair,
+                _ => return Err(RuntimeError::new(
+                    "1st argument to 'parameterize!' must be a list of (parameter value) pairs!",
+                )),
+            };
+
+        let parameter = match interpreter.evaluate(&pair[0])? {
+            MankaiObject::Parameter(parameter) => parameter,
+            _ => {
+                return Err(RuntimeError::new(&format!(
+                    "{}-th binding of 'parameterize!' must be for a parameter!",
+                    i + 1
+                )))
+            }
+        };
+        let value = interpreter.evaluate(&pair[1])?;
+        let value = parameter.convert(interpreter, value)?;
+        values.push((parameter, value));
+    }
+
+    let previous: Vec<_> = values
+        .into_iter()
+        .map(|(parameter, value)| {
+            let old = parameter.replace(value);
+            (parameter, old)
+        })
+        .collect();
+    let result = interpreter.evaluate(arguments.get(1).unwrap());
+    for (parameter, old) in previous.into_iter().rev() {
+        parameter.replace(old);
+    }
+
+    result
+}
+
+// Helpers.
+
+/// Get the name assigned to by `define!` or `set!`, given their arguments.
+/// Return an error if trying to assign to a special form, a native function
+/// or a constant, or if the name is not an identifier.
+pub fn assigned_name<'a>(
+    interpreter: &Interpreter,
+    arguments: &'a [Sexp],
+) -> Result<&'a Token, RuntimeError> {
     // Check that we have exactly two arguments.
     if arguments.len() != 2 {
         return Err(RuntimeError::new(
@@ -229,12 +365,9 @@ pub fn set(
         ));
     }
 
-    // Get token that identifies the name of the variable. Return an error if
-    // trying to define! a special form of a native function or if the token is not
-    // an identifier.
     let name = match arguments.get(0).unwrap() {
         Sexp::Atom(token) => token,
-        Sexp::List(_) => {
+        _ => {
             return Err(RuntimeError::new(
                 "expected identifier as first argument to 'define!'",
             ))
@@ -250,6 +383,14 @@ pub fn set(
         )));
     }
 
+    check_assignable(interpreter, name)?;
+
+    Ok(name)
+}
+
+/// Check that a name can be assigned to, that is it doesn't belong to a
+/// special form, a native function or a constant.
+pub fn check_assignable(interpreter: &Interpreter, name: &Token) -> Result<(), RuntimeError> {
     if interpreter.is_special_form(name) {
         return Err(RuntimeError::new(&format!(
             "can't assign to '{}' because the name is reserved for a special form!",
@@ -271,11 +412,224 @@ pub fn set(
         )));
     }
 
-    // Get the value to assign.
-    let value = interpreter.evaluate(arguments.get(1).unwrap())?;
-    let value_clone = value.clone();
+    Ok(())
+}
+
+/// Parse the `(pattern value)` pairs of a `let!`, given its arguments.
+pub fn parse_let_bindings(arguments: &[Sexp]) -> Result<Vec<(Pattern, Sexp)>, RuntimeError> {
+    // Arity check.
+    if arguments.len() != 2 {
+        return Err(RuntimeError::new("'let!' requires exactly two arguments!"));
+    }
+
+    let mut bindings = Vec::new();
+    match arguments.first().unwrap() {
+        Sexp::List(list) => {
+            for binding in list.iter() {
+                match binding {
+                    Sexp::List(pair) if pair.len() == 2 => {
+                        bindings.push((Pattern::parse_binding(&pair[0])?, pair[1].clone()))
+                    }
+                    _ => {
+                        return Err(RuntimeError::new(
+                            "1st argument to 'let!' must be a list of (pattern value) pairs!",
+                        ))
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(RuntimeError::new(
+                "1st argument to 'let!' must be a list of (pattern value) pairs!",
+            ))
+        }
+    }
+
+    Ok(bindings)
+}
+
+/// Bind the parts of the value of the `position`-th binding of a `let!` to
+/// the variables of its pattern, in the innermost layer of the environment.
+pub fn bind_pattern(
+    interpreter: &mut Interpreter,
+    position: usize,
+    pattern: &Pattern,
+    value: &MankaiObject,
+) -> Result<(), RuntimeError> {
+    let mut variables = Vec::new();
+    if !pattern.matches(interpreter, value, &mut variables)? {
+        return Err(RuntimeError::new(&format!(
+            "value of the {}-th binding of 'let!' must match the pattern {} but is {}!",
+            position,
+            pattern,
+            value.to_string()
+        )));
+    }
+
+    for (identifier, value) in variables {
+        interpreter.environment.define(&identifier, value);
+    }
+
+    Ok(())
+}
+
+/// Get the pattern, the guard (if any) and the body of a clause given as
+/// `position`-th argument to `match!`.
+pub fn parse_match_clause(
+    position: usize,
+    clause: &Sexp,
+) -> Result<(&Sexp, Option<&Sexp>, &Sexp), RuntimeError> {
+    if let Sexp::List(list) = clause {
+        match list.as_slice() {
+            [pattern, body] => return Ok((pattern, None, body)),
+            [pattern, Sexp::Atom(Token {
+                kind: TokenKind::Keyword(keyword),
+                ..
+            }), guard, body]
+                if keyword == "when" =>
+            {
+                return Ok((pattern, Some(guard), body))
+            }
+            _ => (),
+        }
+    }
+
+    Err(RuntimeError::new(&format!(
+        "{}-th argument to 'match!' must be a (pattern body) or a (pattern :when guard body) clause!",
+        position
+    )))
+}
+
+/// Parse the name of a record type followed by the names of its fields, given
+/// to the special form `form`.
+fn parse_record_type(
+    form: &str,
+    sexps: &[&Sexp],
+    family: Option<Rc<str>>,
+) -> Result<Rc<RecordType>, RuntimeError> {
+    let mut names = Vec::new();
+    for sexp in sexps {
+        match sexp {
+            Sexp::Atom(Token {
+                lexeme,
+                kind: TokenKind::Identifier,
+            }) => {
+                // The first name is the name of the type, the others are
+                // fields.
+                if names.len() > 1 && names[1..].contains(lexeme) {
+                    return Err(RuntimeError::new(&format!(
+                        "'{}' got the field '{}' twice!",
+                        form, lexeme
+                    )));
+                }
+                names.push(lexeme.clone());
+            }
+            _ => {
+                return Err(RuntimeError::new(&format!(
+                    "names of types and fields given to '{}' must be identifiers!",
+                    form
+                )))
+            }
+        }
+    }
+
+    let name = names.remove(0);
+    Ok(Rc::new(RecordType {
+        name,
+        fields: names,
+        family,
+    }))
+}
+
+/// Get the constructor, the predicate, the accessors and the updaters of a
+/// record type.
+fn record_functions(record_type: &Rc<RecordType>) -> Vec<RecordFunction> {
+    let mut kinds = vec![
+        RecordFunctionKind::Constructor,
+        RecordFunctionKind::Predicate,
+    ];
+    for i in 0..record_type.fields.len() {
+        kinds.push(RecordFunctionKind::Accessor(i));
+        kinds.push(RecordFunctionKind::Updater(i));
+    }
+
+    kinds
+        .into_iter()
+        .map(|kind| RecordFunction {
+            record_type: record_type.clone(),
+            kind,
+        })
+        .collect()
+}
+
+/// Bind record functions to their names. Nothing is bound if any of the names
+/// is reserved.
+fn define_record_functions(
+    interpreter: &mut Interpreter,
+    functions: Vec<RecordFunction>,
+) -> Result<(), RuntimeError> {
+    let names: Vec<Token> = functions
+        .iter()
+        .map(|function| Token::new(function.name(), TokenKind::Identifier))
+        .collect();
+    for name in names.iter() {
+        check_assignable(interpreter, name)?;
+    }
+
+    for (name, function) in names.iter().zip(functions) {
+        interpreter
+            .environment
+            .define(name, MankaiObject::RecordFunction(function));
+    }
+
+    Ok(())
+}
+
+/// Check if a sexp looks like a function clause i.e. `((arg1 ... argN) body)`.
+/// A list starting with `list` is a destructuring pattern and not a list of
+/// arguments, so `((list x y) z)` is a list of arguments rather than a clause.
+fn is_clause(sexp: &Sexp) -> bool {
+    match sexp {
+        Sexp::List(list) => match list.as_slice() {
+            [Sexp::List(parameters), _] => !matches!(
+                parameters.first(),
+                Some(Sexp::Atom(token)) if token.lexeme == "list"
+            ),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Parse a list of `((arg1 ... argN) body)` clauses given to the special form
+/// `form`.
+fn parse_clauses(form: &str, clauses: &[&Sexp]) -> Result<Vec<Clause>, RuntimeError> {
+    let mut parsed = Vec::new();
+
+    for (i, clause) in clauses.iter().enumerate() {
+        match clause {
+            Sexp::List(list) => match list.as_slice() {
+                [Sexp::List(parameters), body] => parsed.push(Clause {
+                    parameters: Parameters::parse(parameters)?,
+                    body: body.clone(),
+                }),
+                _ => {
+                    return Err(RuntimeError::new(&format!(
+                        "{}th clause of '{}' must be a list of arguments followed by a body!",
+                        i + 1,
+                        form
+                    )))
+                }
+            },
+            _ => {
+                return Err(RuntimeError::new(&format!(
+                    "{}th clause of '{}' must be a list of arguments followed by a body!",
+                    i + 1,
+                    form
+                )))
+            }
+        }
+    }
 
-    // Perform the binding.
-    interpreter.environment.set(name, value)?;
-    Ok(value_clone)
+    Ok(parsed)
 }
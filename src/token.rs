@@ -1,11 +1,23 @@
+use crate::number::Number;
+
 /// Types of tokens.
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenKind {
     String(String),
-    Number(f64),
+    Number(Number),
+    Keyword(String),
+    Char(char),
     Identifier,
     LeftParen,
     RightParen,
+    LeftBrace,
+    RightBrace,
+    /// The `#{` opening a set literal (closed by a `RightBrace`).
+    HashLeftBrace,
+    LeftBracket,
+    RightBracket,
+    /// The `#;` prefix commenting out the following sexp.
+    DatumComment,
     Eof,
 }
 